@@ -0,0 +1,422 @@
+use std::collections::HashMap;
+use crate::{
+    tools::check::{Check, Checker},
+    structure::{
+        position::{Located, Position},
+        program::Program,
+        value::{Type, Value},
+        ast::*
+    }
+};
+
+pub fn check<N: Check<Annotation>>(ast: &N, program: &Program) -> Result<(), Vec<Located<String>>> {
+    let mut globals = HashMap::new();
+    let mut scope = Some(program.scope);
+    while let Some(addr) = scope {
+        let Some(current) = program.scopes.get(addr) else {
+            break
+        };
+        for (ident, value) in current.vars.iter() {
+            if globals.contains_key(ident) {
+                continue
+            }
+            match current.types.get(ident) {
+                Some(typ) => globals.insert(ident.clone(), (typ.clone(), true)),
+                None => globals.insert(ident.clone(), (annotation(value, program), false))
+            };
+        }
+        scope = current.parent;
+    }
+    let mut checker = Checker::new(globals);
+    ast.check(&mut checker);
+    if checker.errors.is_empty() {
+        Ok(())
+    } else {
+        Err(checker.errors)
+    }
+}
+
+pub fn annotation(value: &Value, program: &Program) -> Annotation {
+    match value {
+        Value::Closure(addr) => match program.fns.get(*addr) {
+            Some(function) => function.signature.clone(),
+            None => Annotation::Type(Type::Function)
+        }
+        Value::Vector(values) => Annotation::Vector(Box::new(values.iter()
+            .map(|value| annotation(value, program))
+            .reduce(union)
+            .unwrap_or(Annotation::Any))),
+        value => Annotation::Type(Type::from(value.clone()))
+    }
+}
+
+pub fn assignable(from: &Annotation, to: &Annotation) -> bool {
+    match (from, to) {
+        (Annotation::Any, _) | (_, Annotation::Any) => true,
+        (Annotation::Union(types), to) => types.iter().all(|typ| assignable(typ, to)),
+        (from, Annotation::Union(types)) => types.iter().any(|typ| assignable(from, typ)),
+        (Annotation::None, Annotation::None) => true,
+        (Annotation::Type(Type::Int), Annotation::Type(Type::Float)) => true,
+        (Annotation::Type(from), Annotation::Type(to)) => from == to,
+        (Annotation::Vector(from), Annotation::Vector(to)) => assignable(from, to),
+        (Annotation::Vector(_), Annotation::Type(Type::Vector)) | (Annotation::Type(Type::Vector), Annotation::Vector(_)) => true,
+        (Annotation::Function(_, _), Annotation::Type(Type::Function)) | (Annotation::Type(Type::Function), Annotation::Function(_, _)) => true,
+        (Annotation::Function(from_params, from_ret), Annotation::Function(to_params, to_ret)) => from_params.len() == to_params.len()
+            && from_params.iter().zip(to_params.iter()).all(|(from, to)| assignable(to, from))
+            && assignable(from_ret, to_ret),
+        _ => false
+    }
+}
+
+pub fn union(left: Annotation, right: Annotation) -> Annotation {
+    let mut types: Vec<Annotation> = vec![];
+    for typ in [left, right] {
+        let variants = match typ {
+            Annotation::Any => return Annotation::Any,
+            Annotation::Union(variants) => variants,
+            typ => vec![typ]
+        };
+        for typ in variants {
+            if !types.contains(&typ) {
+                types.push(typ);
+            }
+        }
+    }
+    if types.len() == 1 {
+        return types.remove(0)
+    }
+    Annotation::Union(types)
+}
+
+fn variants(typ: &Annotation) -> Vec<Annotation> {
+    match typ {
+        Annotation::Union(types) => types.clone(),
+        Annotation::Vector(_) => vec![Annotation::Type(Type::Vector)],
+        Annotation::Function(_, _) => vec![Annotation::Type(Type::Function)],
+        typ => vec![typ.clone()]
+    }
+}
+
+pub fn binary(op: BinaryOperator, left: &Annotation, right: &Annotation) -> Option<Annotation> {
+    if *left == Annotation::Any || *right == Annotation::Any {
+        return Some(Annotation::Any)
+    }
+    let mut result = None;
+    for left in variants(left) {
+        for right in variants(right) {
+            let Annotation::Type(right) = right else {
+                return None
+            };
+            let typ = if op == BinaryOperator::In {
+                match (&left, right) {
+                    (_, Type::Vector | Type::Set | Type::Map) | (Annotation::Type(Type::String), Type::String) => Type::Boolean,
+                    _ => return None
+                }
+            } else {
+                let Annotation::Type(left) = left else {
+                    return None
+                };
+                match (op, left, right) {
+                    (BinaryOperator::Div | BinaryOperator::Pow, Type::Int | Type::Float, Type::Int | Type::Float) => Type::Float,
+                    (BinaryOperator::Add | BinaryOperator::Sub | BinaryOperator::Mul | BinaryOperator::Mod, Type::Int, Type::Int) => Type::Int,
+                    (BinaryOperator::Add | BinaryOperator::Sub | BinaryOperator::Mul | BinaryOperator::Mod, Type::Int | Type::Float, Type::Int | Type::Float) => Type::Float,
+                    (BinaryOperator::BitOr | BinaryOperator::BitXor | BinaryOperator::BitAnd | BinaryOperator::Shl | BinaryOperator::Shr, Type::Int, Type::Int) => Type::Int,
+                    (BinaryOperator::BitOr | BinaryOperator::BitXor | BinaryOperator::BitAnd, Type::Boolean, Type::Boolean) => Type::Boolean,
+                    (BinaryOperator::Sub | BinaryOperator::Pow | BinaryOperator::BitOr | BinaryOperator::BitXor | BinaryOperator::BitAnd, Type::Set, Type::Set) => Type::Set,
+                    (BinaryOperator::Add | BinaryOperator::Sub, Type::Duration, Type::Duration) => Type::Duration,
+                    (BinaryOperator::Add | BinaryOperator::Sub, Type::Date, Type::Duration) | (BinaryOperator::Add, Type::Duration, Type::Date) => Type::Date,
+                    (BinaryOperator::Sub, Type::Date, Type::Date) => Type::Duration,
+                    (BinaryOperator::Mul, Type::Duration, Type::Int | Type::Float) | (BinaryOperator::Mul, Type::Int | Type::Float, Type::Duration) => Type::Duration,
+                    (BinaryOperator::Div, Type::Duration, Type::Int | Type::Float) => Type::Duration,
+                    (BinaryOperator::Div, Type::Duration, Type::Duration) => Type::Float,
+                    _ => return None
+                }
+            };
+            result = Some(match result {
+                Some(result) => union(result, Annotation::Type(typ)),
+                None => Annotation::Type(typ)
+            });
+        }
+    }
+    result
+}
+
+pub fn unary(op: UnaryOperator, right: &Annotation) -> Option<Annotation> {
+    if *right == Annotation::Any {
+        return Some(Annotation::Any)
+    }
+    let mut result = None;
+    for right in variants(right) {
+        let typ = match (op, right) {
+            (UnaryOperator::Neg, Annotation::Type(Type::Int)) | (UnaryOperator::BitNot, Annotation::Type(Type::Int)) => Type::Int,
+            (UnaryOperator::Neg, Annotation::Type(Type::Float)) => Type::Float,
+            (UnaryOperator::Neg, Annotation::Type(Type::Duration)) => Type::Duration,
+            (UnaryOperator::Not, Annotation::Type(Type::Boolean)) => Type::Boolean,
+            _ => return None
+        };
+        result = Some(match result {
+            Some(result) => union(result, Annotation::Type(typ)),
+            None => Annotation::Type(typ)
+        });
+    }
+    result
+}
+
+fn strip_none(typ: Annotation) -> Annotation {
+    match typ {
+        Annotation::Union(types) => types.into_iter()
+            .filter(|typ| *typ != Annotation::None)
+            .reduce(union)
+            .unwrap_or(Annotation::None),
+        typ => typ
+    }
+}
+
+/// whether the variable an index or field path starts at has an explicit annotation
+fn declared(atom: &Atom, checker: &Checker<Annotation>) -> bool {
+    match atom {
+        Atom::Ident(ident) => checker.get(ident).is_some_and(|(_, declared)| *declared),
+        Atom::Index { head, field: _, optional: _ } | Atom::Field { head, field: _, optional: _ } => declared(&head.value, checker),
+        _ => false
+    }
+}
+
+impl Check<Annotation> for Located<Atom> {
+    fn check(&self, checker: &mut Checker<Annotation>) -> Annotation {
+        match &self.value {
+            Atom::Int(_) => Annotation::Type(Type::Int),
+            Atom::Duration(_) => Annotation::Type(Type::Duration),
+            Atom::Float(_) => Annotation::Type(Type::Float),
+            Atom::Boolean(_) => Annotation::Type(Type::Boolean),
+            Atom::None => Annotation::None,
+            Atom::String(_) => Annotation::Type(Type::String),
+            Atom::Regex(_) => Annotation::Type(Type::Regex),
+            Atom::Ident(ident) => match checker.get(ident) {
+                Some((typ, _)) => typ.clone(),
+                None => Annotation::Any
+            }
+            Atom::Expression(expr) => expr.check(checker),
+            Atom::Vector(exprs) => Annotation::Vector(Box::new(exprs.iter()
+                .map(|expr| expr.check(checker))
+                .reduce(union)
+                .unwrap_or(Annotation::Any))),
+            Atom::Map(entries) => {
+                for (key, value) in entries {
+                    key.check(checker);
+                    value.check(checker);
+                }
+                Annotation::Type(Type::Map)
+            }
+            Atom::Set(exprs) => {
+                for expr in exprs {
+                    expr.check(checker);
+                }
+                Annotation::Type(Type::Set)
+            }
+            Atom::Field { head, field: _, optional: _ } => {
+                head.check(checker);
+                Annotation::Any
+            }
+            Atom::Index { head, field, optional } => {
+                let head = head.check(checker);
+                field.check(checker);
+                match head {
+                    Annotation::Vector(typ) if *optional => union(*typ, Annotation::None),
+                    Annotation::Vector(typ) => *typ,
+                    _ => Annotation::Any
+                }
+            }
+        }
+    }
+}
+
+impl Check<Annotation> for Located<Expression> {
+    fn check(&self, checker: &mut Checker<Annotation>) -> Annotation {
+        let Located { value: expr, pos } = self;
+        match expr {
+            Expression::Atom(atom) => Located::new(atom.clone(), pos.clone()).check(checker),
+            Expression::Binary { op: BinaryOperator::Coalesce, left, right } => {
+                let left = left.check(checker);
+                let right = right.check(checker);
+                match left {
+                    Annotation::None => right,
+                    left => union(strip_none(left), right)
+                }
+            }
+            Expression::Binary { op, left, right } => {
+                let left = left.check(checker);
+                let right = right.check(checker);
+                match binary(*op, &left, &right) {
+                    Some(typ) => typ,
+                    None => {
+                        checker.error(format!("cannot perform binary operator {op:?} on {left} with {right}"), pos.clone());
+                        Annotation::Any
+                    }
+                }
+            }
+            Expression::Unary { op, right } => {
+                let right = right.check(checker);
+                match unary(*op, &right) {
+                    Some(typ) => typ,
+                    None => {
+                        checker.error(format!("cannot perform unary operator {op:?} on {right}"), pos.clone());
+                        Annotation::Any
+                    }
+                }
+            }
+            Expression::Call { head, args } => {
+                let head_type = head.check(checker);
+                let arg_types: Vec<Annotation> = args.value.0.iter().map(|arg| arg.check(checker)).collect();
+                match head_type {
+                    Annotation::Function(params, ret) => {
+                        if params.len() != arg_types.len() {
+                            checker.error(format!("expected {} arguments, got {}", params.len(), arg_types.len()), args.pos.clone());
+                        }
+                        for (i, ((param, typ), arg)) in params.iter().zip(arg_types.iter()).zip(args.value.0.iter()).enumerate() {
+                            if !assignable(typ, param) {
+                                checker.error(format!("expected {param} for argument #{}, got {typ}", i + 1), arg.pos.clone());
+                            }
+                        }
+                        *ret
+                    }
+                    Annotation::Any | Annotation::Type(Type::Function) | Annotation::Union(_) => Annotation::Any,
+                    typ => {
+                        checker.error(format!("cannot call {typ}"), head.pos.clone());
+                        Annotation::Any
+                    }
+                }
+            }
+            Expression::Try { body, catch, finally } => {
+                let mut typ = body.check(checker);
+                if let Some((ident, block)) = catch {
+                    checker.push();
+                    checker.set(&ident.value, Annotation::Type(Type::Object), false);
+                    typ = union(typ, block.check(checker));
+                    checker.pop();
+                }
+                if let Some(block) = finally {
+                    block.check(checker);
+                }
+                typ
+            }
+            Expression::Import { name: _, path: _ } => Annotation::Type(Type::Object),
+            Expression::Let { ident, annotation, expr, constant: _ } => {
+                let typ = expr.check(checker);
+                match annotation {
+                    Some(annotation) => {
+                        if variants(&annotation.value).contains(&Annotation::None) {
+                            checker.error(format!("variables cannot hold none, remove it from {}", annotation.value), annotation.pos.clone());
+                        } else if !assignable(&typ, &annotation.value) {
+                            checker.error(format!("expected {}, got {typ}", annotation.value), expr.pos.clone());
+                        }
+                        checker.set(&ident.value, annotation.value.clone(), true);
+                        annotation.value.clone()
+                    }
+                    None => {
+                        checker.set(&ident.value, typ.clone(), false);
+                        typ
+                    }
+                }
+            }
+            Expression::Assign { target, expr } => {
+                let typ = expr.check(checker);
+                let expected = match &target.value {
+                    Atom::Ident(ident) => checker.get(ident).cloned(),
+                    Atom::Index { head, field, optional: _ } => {
+                        field.check(checker);
+                        match head.check(checker) {
+                            Annotation::Vector(elem) => Some((*elem, declared(&head.value, checker))),
+                            _ => None
+                        }
+                    }
+                    _ => {
+                        target.check(checker);
+                        None
+                    }
+                };
+                match expected {
+                    Some((expected, true)) if !assignable(&typ, &expected) => {
+                        checker.error(format!("expected {expected}, got {typ}"), expr.pos.clone());
+                    }
+                    Some((_, false)) => match &target.value {
+                        Atom::Ident(ident) => checker.assign(ident, |old| union(old.clone(), typ.clone())),
+                        Atom::Index { head, field: _, optional: _ } => if let Atom::Ident(ident) = &head.value {
+                            checker.assign(ident, |old| match old {
+                                Annotation::Vector(elem) => Annotation::Vector(Box::new(union(*elem.clone(), typ.clone()))),
+                                old => old.clone()
+                            });
+                        }
+                        _ => {}
+                    }
+                    _ => {}
+                }
+                typ
+            }
+            Expression::Function { params, ret, body, generator } => {
+                checker.push();
+                for (param, annotation) in params {
+                    match annotation {
+                        Some(annotation) => checker.set(&param.value, annotation.value.clone(), true),
+                        None => checker.set(&param.value, Annotation::Any, false)
+                    }
+                }
+                let body_type = body.check(checker);
+                checker.pop();
+                let params = params.iter()
+                    .map(|(_, annotation)| annotation.as_ref().map(|annotation| annotation.value.clone()).unwrap_or(Annotation::Any))
+                    .collect();
+                if *generator {
+                    return Annotation::Function(params, Box::new(Annotation::Type(Type::Iterator)))
+                }
+                match ret {
+                    Some(ret) => {
+                        if !assignable(&body_type, &ret.value) {
+                            let pos = body.value.0.last().map(|expr| expr.pos.clone()).unwrap_or(body.pos.clone());
+                            checker.error(format!("expected {}, got {body_type}", ret.value), pos);
+                        }
+                        Annotation::Function(params, Box::new(ret.value.clone()))
+                    }
+                    None => Annotation::Function(params, Box::new(body_type))
+                }
+            }
+            Expression::For { ident, iter, body } => {
+                let typ = match iter.check(checker) {
+                    Annotation::Vector(typ) => *typ,
+                    Annotation::Type(Type::String) => Annotation::Type(Type::String),
+                    _ => Annotation::Any
+                };
+                checker.push();
+                checker.set(&ident.value, typ, false);
+                body.check(checker);
+                checker.pop();
+                Annotation::None
+            }
+            Expression::Yield(expr) => {
+                expr.check(checker);
+                Annotation::None
+            }
+        }
+    }
+}
+
+impl Check<Annotation> for Located<Block> {
+    fn check(&self, checker: &mut Checker<Annotation>) -> Annotation {
+        checker.push();
+        let mut typ = Annotation::None;
+        for expr in self.value.0.iter() {
+            typ = expr.check(checker);
+        }
+        checker.pop();
+        typ
+    }
+}
+
+impl Check<Annotation> for Located<Chunk> {
+    fn check(&self, checker: &mut Checker<Annotation>) -> Annotation {
+        let mut typ = Annotation::None;
+        for expr in self.value.0.iter() {
+            typ = expr.check(checker);
+        }
+        typ
+    }
+}
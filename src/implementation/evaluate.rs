@@ -1,34 +1,514 @@
-use std::collections::HashMap;
+use std::{collections::{HashMap, HashSet}, fs, path::{Path, PathBuf}};
 use crate::{
     tools::{
         parse::Parsable,
         evaluate::Evaluate
     },
+    implementation::{
+        lex::lex,
+        parse::parse,
+        check::check
+    },
     structure::{
-        position::Located,
-        program::Program,
+        position::{Located, Position},
+        program::{Program, Scope},
+        tokens::Token,
+        error::Error,
         ast::*,
-        value::*
-    }
+        value::*,
+        time::Date
+    },
+    stdlib::regex::Regex
 };
 
-pub fn evaluate<N: Evaluate<Value, Program>>(ast: N, program: &mut Program) -> Result<Option<Value>, Located<String>> {
+pub fn evaluate<N: Evaluate<Value, Program, Error>>(ast: N, program: &mut Program) -> Result<Option<Value>, Located<Error>> {
     ast.evaluate(program)
 }
 
-impl Evaluate<Value, Program> for Located<Atom> {
-    fn evaluate(self, program: &mut Program) -> Result<Option<Value>, Located<String>> {
+pub fn error_object(err: Located<Error>, program: &mut Program) -> Value {
+    let Located { value: err, pos } = err;
+    let pos = Object::default()
+        .insert("ln", Value::Int(pos.ln.start as i64 + 1))
+        .insert("col", Value::Int(pos.col.start as i64 + 1));
+    let pos = Value::Object(program.objects.create(pos));
+    let err = Object::default()
+        .insert("message", Value::String(err.to_string()))
+        .insert("kind", Value::String(err.kind().to_string()))
+        .insert("pos", pos);
+    Value::Object(program.objects.create(err))
+}
+
+pub fn import(path: &str, program: &mut Program) -> Result<Value, Error> {
+    let path = match program.importing.last().and_then(|importer| importer.parent()) {
+        Some(dir) => dir.join(path),
+        None => PathBuf::from(path)
+    };
+    let file = path.display().to_string();
+    let full_path = fs::canonicalize(&path).map_err(|err| format!("cannot import {file:?}: {err}"))?;
+    if !program.capabilities.read.allows(&full_path) {
+        return Err(format!("permission denied: cannot import {file:?}").into())
+    }
+    if let Some(module) = program.modules.get(&full_path) {
+        return Ok(module.clone())
+    }
+    if let Some(idx) = program.importing.iter().position(|importing| fs::canonicalize(importing).ok() == Some(full_path.clone())) {
+        let cycle = program.importing[idx..].iter()
+            .map(|importing| importing.display().to_string())
+            .chain(std::iter::once(file))
+            .collect::<Vec<String>>().join(" -> ");
+        return Err(format!("import cycle: {cycle}").into())
+    }
+    let input = fs::read_to_string(&full_path).map_err(|err| format!("cannot import {file:?}: {err}"))?;
+    let locate = |Located { value: err, pos }: Located<Error>| {
+        err.map(|msg| format!("{file}:{}:{}: {msg}", pos.ln.start + 1, pos.col.start + 1))
+    };
+    let tokens = lex(input).map_err(|err| locate(err.into()))?;
+    let chunk = parse::<Token, Chunk>(tokens).map_err(|err| locate(err.into()))?;
+    check(&chunk, program).map_err(|errors| errors.into_iter()
+        .map(|Located { value: msg, pos }| format!("{file}:{}:{}: {msg}", pos.ln.start + 1, pos.col.start + 1))
+        .collect::<Vec<String>>()
+        .join("\n"))?;
+    let scope = program.scope;
+    program.scope = program.scopes.create(Scope::new(Some(0)));
+    program.importing.push(path);
+    let result = chunk.evaluate(program);
+    program.importing.pop();
+    let module_scope = std::mem::replace(&mut program.scope, scope);
+    result.map_err(locate)?;
+    let mut module = Object::default();
+    if let Some(Scope { vars, consts: _, types: _, parent: _ }) = program.scopes.get(module_scope) {
+        module.map = vars.clone();
+    }
+    let module = Value::Object(program.objects.create(module));
+    program.modules.insert(full_path, module.clone());
+    Ok(module)
+}
+
+fn time_result(value: Option<Value>, pos: &Position) -> Result<Option<Value>, Located<Error>> {
+    match value {
+        Some(value) => Ok(Some(value)),
+        None => Err(Located::new("time value out of range".into(), pos.clone()))
+    }
+}
+fn scale(duration: i64, factor: f64) -> Option<i64> {
+    let scaled = (duration as f64 * factor).round();
+    if scaled.is_finite() && scaled.abs() < i64::MAX as f64 {
+        Some(scaled as i64)
+    } else {
+        None
+    }
+}
+
+pub fn call(head: Value, args: Vec<Value>, program: &mut Program, pos: Position) -> Result<Option<Value>, Located<Error>> {
+    match head {
+        Value::Function(addr, _) => {
+            let native_fn = program.native_fns.get(addr).unwrap().0.clone();
+            let caller = std::mem::replace(&mut program.pos, pos.clone());
+            let result = native_fn(args, program);
+            program.pos = caller;
+            result.map_err(|err| match err {
+                Error::At(err) => *err,
+                err => Located::new(err, pos)
+            })
+        }
+        Value::Closure(addr) => {
+            let Function { params, body: _, signature: _, scope: _, generator } = program.fns.get(addr).unwrap();
+            if args.len() != params.len() {
+                return Err(Located::new(format!("expected {} arguments, got {}", params.len(), args.len()).into(), pos))
+            }
+            if *generator {
+                return Ok(Some(generate(addr, args, program)))
+            }
+            run(addr, args, program)
+        }
+        head => Err(Located::new(format!("cannot call {}", Type::from(head)).into(), pos))
+    }
+}
+
+/// binds the arguments and suspends the generator before its first statement
+fn generate(addr: usize, args: Vec<Value>, program: &mut Program) -> Value {
+    let Function { params, body, signature: _, scope, generator: _ } = program.fns.get(addr).unwrap().clone();
+    let previous = program.scope;
+    program.enter_scope(scope);
+    for (param, arg) in params.into_iter().zip(args) {
+        program.set(&param.value, arg);
+    }
+    let mut frames = vec![];
+    enter_block(body, &mut frames, program);
+    program.scope = previous;
+    Value::Iterator(program.iterators.create(Iter::Generator(frames)))
+}
+/// suspended scopes outlive the statement that created them, so they are captured like a closure's
+fn enter_block(block: Located<Block>, frames: &mut Vec<Frame>, program: &mut Program) {
+    program.push_scope();
+    let scope = program.capture();
+    frames.push(Frame::Block { exprs: block.value.0, idx: 0, scope });
+}
+/// runs the generator until its next yield, returning none once it is exhausted
+fn resume(frames: &mut Vec<Frame>, program: &mut Program) -> Result<Option<Value>, Located<Error>> {
+    loop {
+        let step = match frames.last_mut() {
+            None => return Ok(None),
+            Some(Frame::Block { exprs, idx, scope }) => {
+                program.scope = *scope;
+                match exprs.get(*idx) {
+                    Some(expr) => {
+                        let expr = expr.clone();
+                        *idx += 1;
+                        statement(expr, frames, program)
+                    }
+                    None => {
+                        frames.pop();
+                        Ok(None)
+                    }
+                }
+            }
+            Some(Frame::For { ident, iter, pos, body, scope }) => {
+                program.scope = *scope;
+                let (ident, body) = (ident.value.clone(), body.clone());
+                match next(*iter, program, &pos.clone()) {
+                    Ok(Some(value)) => {
+                        program.push_scope();
+                        program.set(&ident, value);
+                        enter_block(body, frames, program);
+                        Ok(None)
+                    }
+                    Ok(None) => {
+                        frames.pop();
+                        Ok(None)
+                    }
+                    Err(err) => Err(err)
+                }
+            }
+            Some(Frame::Try { catch: _, finally, scope }) => {
+                program.scope = *scope;
+                let finally = finally.take();
+                frames.pop();
+                match finally {
+                    Some(block) => block.evaluate(program).map(|_| None),
+                    None => Ok(None)
+                }
+            }
+        };
+        match step {
+            Ok(Some(value)) => return Ok(Some(value)),
+            Ok(None) => {}
+            Err(err) => unwind(err, frames, program)?
+        }
+    }
+}
+/// executes a statement of a generator, entering a frame if it can suspend
+fn statement(expr: Located<Expression>, frames: &mut Vec<Frame>, program: &mut Program) -> Result<Option<Value>, Located<Error>> {
+    if !expr.value.yields() {
+        expr.evaluate(program)?;
+        return Ok(None)
+    }
+    let Located { value: expr, pos } = expr;
+    match expr {
+        Expression::Atom(Atom::Expression(expr)) => statement(*expr, frames, program),
+        Expression::Yield(expr) => {
+            let expr_pos = expr.pos.clone();
+            match expr.evaluate(program)? {
+                Some(value) => Ok(Some(value)),
+                None => Err(Located::new("return value is none".into(), expr_pos))
+            }
+        }
+        Expression::For { ident, iter, body } => {
+            let iter_pos = iter.pos.clone();
+            let Some(iter) = iter.evaluate(program)? else {
+                return Err(Located::new("return value is none".into(), iter_pos))
+            };
+            let iter = iterate(iter, program).map_err(|err| Located::new(err, iter_pos.clone()))?;
+            frames.push(Frame::For { ident, iter, pos: iter_pos, body, scope: program.scope });
+            Ok(None)
+        }
+        Expression::Try { body, catch, finally } => {
+            frames.push(Frame::Try { catch, finally, scope: program.scope });
+            statement(*body, frames, program)
+        }
+        _ => Err(Located::new("yield can only be used as a statement".into(), pos))
+    }
+}
+/// pops frames up to the innermost try that catches the error, running finally blocks on the way
+fn unwind(mut err: Located<Error>, frames: &mut Vec<Frame>, program: &mut Program) -> Result<(), Located<Error>> {
+    while let Some(frame) = frames.pop() {
+        let Frame::Try { catch, finally, scope } = frame else {
+            continue
+        };
+        program.scope = scope;
+        if let (Some((ident, block)), Error::User(_) | Error::Runtime(_)) = (catch, &err.value) {
+            let err = error_object(err, program);
+            frames.push(Frame::Try { catch: None, finally, scope });
+            program.push_scope();
+            program.set(&ident.value, err);
+            enter_block(block, frames, program);
+            return Ok(())
+        }
+        if let Some(block) = finally {
+            if let Err(finally_err) = block.evaluate(program) {
+                err = finally_err;
+            }
+        }
+    }
+    Err(err)
+}
+
+fn run(addr: usize, args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Located<Error>> {
+    let Function { params, body, signature: _, scope, generator: _ } = program.fns.get(addr).unwrap().clone();
+    let previous = program.scope;
+    let mark = program.enter_scope(scope);
+    for (param, arg) in params.into_iter().zip(args) {
+        program.set(&param.value, arg);
+    }
+    let result = body.evaluate(program);
+    program.leave_scope(previous, mark);
+    result
+}
+
+pub fn iterate(value: Value, program: &mut Program) -> Result<usize, Error> {
+    let values = match value {
+        Value::Iterator(addr) => return Ok(addr),
+        Value::Vector(values) => values,
+        Value::Set(values) => values.into_iter().collect(),
+        Value::Map(map) => map.into_keys().collect(),
+        Value::String(string) => string.chars().map(|c| Value::String(c.to_string())).collect(),
+        value => return Err(format!("cannot iterate over {}", Type::from(value)).into())
+    };
+    Ok(program.iterators.create(Iter::Values(values.into_iter())))
+}
+
+pub fn next(addr: usize, program: &mut Program, pos: &Position) -> Result<Option<Value>, Located<Error>> {
+    let Some(iter) = program.iterators.get_mut(addr) else {
+        return Ok(None)
+    };
+    match iter {
+        Iter::Values(values) => Ok(values.next()),
+        Iter::Generator(frames) => {
+            let mut frames = std::mem::take(frames);
+            let scope = program.scope;
+            let result = resume(&mut frames, program);
+            program.scope = scope;
+            if let Some(Iter::Generator(suspended)) = program.iterators.get_mut(addr) {
+                *suspended = frames;
+            }
+            result
+        }
+        Iter::Map { iter, func } => {
+            let (iter, func) = (*iter, func.clone());
+            let Some(value) = next(iter, program, pos)? else {
+                return Ok(None)
+            };
+            call(func, vec![value], program, pos.clone())
+        }
+        Iter::Filter { iter, func } => {
+            let (iter, func) = (*iter, func.clone());
+            while let Some(value) = next(iter, program, pos)? {
+                match call(func.clone(), vec![value.clone()], program, pos.clone())? {
+                    Some(Value::Boolean(true)) => return Ok(Some(value)),
+                    Some(Value::Boolean(false)) => continue,
+                    Some(keep) => return Err(Located::new(format!("expected boolean from filter function, got {}", Type::from(keep)).into(), pos.clone())),
+                    None => return Err(Located::new("expected boolean from filter function, got none".into(), pos.clone()))
+                }
+            }
+            Ok(None)
+        }
+        Iter::Take { iter, count } => {
+            if *count == 0 {
+                return Ok(None)
+            }
+            *count -= 1;
+            let iter = *iter;
+            next(iter, program, pos)
+        }
+        Iter::Zip(left, right) => {
+            let (left, right) = (*left, *right);
+            let Some(left) = next(left, program, pos)? else {
+                return Ok(None)
+            };
+            let Some(right) = next(right, program, pos)? else {
+                return Ok(None)
+            };
+            Ok(Some(Value::Vector(vec![left, right])))
+        }
+    }
+}
+
+fn index_mut<'a>(head: &'a mut Value, field: &Located<Value>, pos: &Position) -> Result<&'a mut Value, Located<Error>> {
+    match head {
+        Value::Vector(values) => match &field.value {
+            Value::Int(index) => {
+                let len = values.len();
+                match usize::try_from(*index).ok().and_then(|idx| values.get_mut(idx)) {
+                    Some(value) => Ok(value),
+                    None => Err(Located::new(format!("index {index} out of range for length {len}").into(), field.pos.clone()))
+                }
+            }
+            key => Err(Located::new(format!("cannot index with {}", Type::from(key.clone())).into(), pos.clone()))
+        }
+        Value::Map(map) => match map.get_mut(&field.value) {
+            Some(value) => Ok(value),
+            None => Err(Located::new(format!("no key {:?}", field.value).into(), field.pos.clone()))
+        }
+        head => Err(Located::new(format!("cannot index {}", Type::from(head.clone())).into(), pos.clone()))
+    }
+}
+
+fn assign_index(target: Located<Atom>, value: Value, program: &mut Program) -> Result<(), Located<Error>> {
+    let Located { value: mut target, pos } = target;
+    let mut fields = vec![];
+    while let Atom::Index { head, field, optional: _ } = target {
+        fields.push(*field);
+        target = head.value;
+    }
+    let mut keys = vec![];
+    for field in fields.into_iter().rev() {
+        let field_pos = field.pos.clone();
+        let Some(key) = field.evaluate(program)? else {
+            return Err(Located::new("return value is none".into(), field_pos))
+        };
+        keys.push(Located::new(key, field_pos));
+    }
+    let mut head = match target {
+        Atom::Ident(ident) => {
+            if program.is_const(&ident) {
+                return Err(Located::new(format!("cannot assign to constant {ident:?}").into(), pos))
+            }
+            match program.get_mut(&ident) {
+                Some(head) => head,
+                None => return Err(Located::new(format!("no variable with the name {ident:?} found").into(), pos))
+            }
+        }
+        Atom::Field { head, field, optional: _ } => {
+            let head_pos = head.pos.clone();
+            let Some(head) = head.evaluate(program)? else {
+                return Err(Located::new("return value is none".into(), head_pos))
+            };
+            let Value::Object(addr) = head else {
+                return Err(Located::new(format!("cannot get field of {}", Type::from(head)).into(), pos))
+            };
+            let obj = program.objects.get_mut(addr).unwrap();
+            if obj.frozen {
+                return Err(Located::new(format!("cannot set field {:?} of frozen object", field.value).into(), pos))
+            }
+            match obj.map.get_mut(&field.value) {
+                Some(head) => head,
+                None => return Err(Located::new(format!("no field named {:?}", field.value).into(), field.pos))
+            }
+        }
+        _ => return Err(Located::new("cannot assign to this expression".into(), pos))
+    };
+    let Some(key) = keys.pop() else {
+        return Err(Located::new("cannot assign to this expression".into(), pos))
+    };
+    for key in keys.iter() {
+        head = index_mut(head, key, &pos)?;
+    }
+    match head {
+        Value::Vector(values) => match key.value {
+            Value::Int(index) => match usize::try_from(index) {
+                Ok(idx) if idx < values.len() => values[idx] = value,
+                Ok(idx) if idx == values.len() => values.push(value),
+                _ => return Err(Located::new(format!("index {index} out of range for length {}", values.len()).into(), key.pos))
+            }
+            key => return Err(Located::new(format!("cannot index with {}", Type::from(key)).into(), pos))
+        }
+        Value::Map(map) => {
+            map.insert(key.value, value);
+        }
+        head => return Err(Located::new(format!("cannot index {}", Type::from(head.clone())).into(), pos))
+    }
+    Ok(())
+}
+
+/// evaluates a field or index path, returning none as a whole once an optional access short-circuits it
+fn access(atom: Located<Atom>, program: &mut Program) -> Result<Option<Option<Value>>, Located<Error>> {
+    let Located { value: atom, pos } = atom;
+    match atom {
+        Atom::Field { head, field, optional } => {
+            let head_pos = head.pos.clone();
+            let Some(head) = path(*head, program)? else {
+                return Ok(None)
+            };
+            let Some(head) = head else {
+                if optional {
+                    return Ok(None)
+                }
+                return Err(Located::new("return value is none".into(), head_pos))
+            };
+            match head {
+                Value::Object(addr) => {
+                    let obj = &program.objects.get(addr).unwrap();
+                    if let Some(value) = obj.map.get(&field.value) {
+                        Ok(Some(Some(value.clone())))
+                    } else if optional {
+                        Ok(Some(None))
+                    } else {
+                        Err(Located::new(format!("no field named {:?}", field.value).into(), field.pos))
+                    }
+                }
+                head => Err(Located::new(format!("cannot get field of {}", Type::from(head)).into(), pos))
+            }
+        }
+        Atom::Index { head, field, optional } => {
+            let head_pos = head.pos.clone();
+            let Some(head) = path(*head, program)? else {
+                return Ok(None)
+            };
+            let Some(head) = head else {
+                if optional {
+                    return Ok(None)
+                }
+                return Err(Located::new("return value is none".into(), head_pos))
+            };
+            let field_pos = field.pos.clone();
+            let Some(field) = field.evaluate(program)? else {
+                return Err(Located::new("return value is none".into(), field_pos))
+            };
+            match head {
+                Value::Vector(values) => match field {
+                    Value::Int(index) => {
+                        let idx = if index >= 0 {
+                            index as usize
+                        } else {
+                            return Err(Located::new(format!("invalid index: {index}").into(), field_pos));
+                        };
+                        Ok(Some(values.get(idx).cloned()))
+                    }
+                    field => Err(Located::new(format!("cannot index with {}", Type::from(field)).into(), pos))
+                }
+                Value::Map(map) => Ok(Some(map.get(&field).cloned())),
+                head => Err(Located::new(format!("cannot index {}", Type::from(head)).into(), pos))
+            }
+        }
+        _ => unreachable!("only field and index paths are accessed")
+    }
+}
+
+fn path(head: Located<Atom>, program: &mut Program) -> Result<Option<Option<Value>>, Located<Error>> {
+    match head.value {
+        Atom::Field { .. } | Atom::Index { .. } => access(head, program),
+        _ => head.evaluate(program).map(Some)
+    }
+}
+
+impl Evaluate<Value, Program, Error> for Located<Atom> {
+    fn evaluate(self, program: &mut Program) -> Result<Option<Value>, Located<Error>> {
         let Located { value: atom, pos } = self;
         match atom {
             Atom::Int(v) => Ok(Some(Value::Int(v))),
+            Atom::Duration(v) => Ok(Some(Value::Duration(v))),
             Atom::Float(v) => Ok(Some(Value::Float(v))),
             Atom::Boolean(v) => Ok(Some(Value::Boolean(v))),
             Atom::None => Ok(None),
             Atom::String(v) => Ok(Some(Value::String(v))),
-            Atom::Ident(ident) => if let Some(value) = program.vars.get(&ident) {
+            Atom::Regex(pattern) => {
+                let regex = Regex::new(&pattern).map_err(|err| Located::new(err, pos))?;
+                Ok(Some(Value::Regex(program.regexes.create(regex))))
+            }
+            Atom::Ident(ident) => if let Some(value) = program.get(&ident) {
                 Ok(Some(value.clone()))
             } else {
-                Err(Located::new(format!("no variable with the name {ident:?} found"), pos))
+                Err(Located::new(format!("no variable with the name {ident:?} found").into(), pos))
             }
             Atom::Expression(expr) => expr.evaluate(program),
             Atom::Vector(exprs) => {
@@ -43,56 +523,48 @@ impl Evaluate<Value, Program> for Located<Atom> {
                 }
                 Ok(Some(Value::Vector(values)))
             }
-            Atom::Field { head, field } => {
-                let head_pos = head.pos.clone();
-                let Some(head) = head.evaluate(program)? else {
-                    return Err(Located::new("return value is none".into(), head_pos))
-                };
-                match head {
-                    Value::Object(addr) => {
-                        let obj = &program.objects.get(addr).unwrap();
-                        if let Some(value) = obj.map.get(&field.value) {
-                            Ok(Some(value.clone()))
-                        } else {
-                            Err(Located::new(format!("no field named {:?}", field.value), field.pos))
-                        }
-                    }
-                    head => Err(Located::new(format!("cannot get field of {}", Type::from(head)), pos))
+            Atom::Map(entries) => {
+                let mut map = HashMap::new();
+                for (key, value) in entries {
+                    let key_pos = key.pos.clone();
+                    let Some(key) = key.evaluate(program)? else {
+                        return Err(Located::new("return value is none".into(), key_pos))
+                    };
+                    let value_pos = value.pos.clone();
+                    let Some(value) = value.evaluate(program)? else {
+                        return Err(Located::new("return value is none".into(), value_pos))
+                    };
+                    map.insert(key, value);
                 }
+                Ok(Some(Value::Map(map)))
             }
-            Atom::Index { head, field } => {
-                let head_pos = head.pos.clone();
-                let Some(head) = head.evaluate(program)? else {
-                    return Err(Located::new("return value is none".into(), head_pos))
-                };
-                let field_pos = field.pos.clone();
-                let Some(field) = field.evaluate(program)? else {
-                    return Err(Located::new("return value is none".into(), field_pos))
-                };
-                match head {
-                    Value::Vector(values) => match field {
-                        Value::Int(index) => {
-                            let idx = if index >= 0 {
-                                index as usize
-                            } else {
-                                return Err(Located::new(format!("invalid index: {index}"), field_pos));
-                            };
-                            Ok(values.get(idx).cloned())
-                        }
-                        field => Err(Located::new(format!("cannot index with {}", Type::from(field)), pos))
-                    }
-                    head => Err(Located::new(format!("cannot index {}", Type::from(head)), pos))
+            Atom::Set(exprs) => {
+                let mut values = HashSet::new();
+                for expr in exprs {
+                    let expr_pos = expr.pos.clone();
+                    let Some(value) = expr.evaluate(program)? else {
+                        return Err(Located::new("return value is none".into(), expr_pos))
+                    };
+                    values.insert(value);
                 }
+                Ok(Some(Value::Set(values)))
             }
+            atom @ (Atom::Field { .. } | Atom::Index { .. }) => access(Located::new(atom, pos), program).map(Option::flatten),
         }
     }
 }
 
-impl Evaluate<Value, Program> for Located<Expression> {
-    fn evaluate(self, program: &mut Program) -> Result<Option<Value>, Located<String>> {
+impl Evaluate<Value, Program, Error> for Located<Expression> {
+    fn evaluate(self, program: &mut Program) -> Result<Option<Value>, Located<Error>> {
         let Located { value: expr, pos } = self;
         match expr {
             Expression::Atom(atom) => Located::new(atom, pos).evaluate(program),
+            Expression::Binary { op: BinaryOperator::Coalesce, left, right } => {
+                if let Some(value) = left.evaluate(program)? {
+                    return Ok(Some(value))
+                }
+                right.evaluate(program)
+            }
             Expression::Binary { op, left, right } => {
                 let left_pos = left.pos.clone();
                 let Some(left) = left.evaluate(program)? else {
@@ -108,42 +580,95 @@ impl Evaluate<Value, Program> for Located<Expression> {
                         (Value::Float(v1), Value::Float(v2)) => Ok(Some(Value::Float(v1 + v2))),
                         (Value::Int(v1), Value::Float(v2)) => Ok(Some(Value::Float(v1 as f64 + v2))),
                         (Value::Float(v1), Value::Int(v2)) => Ok(Some(Value::Float(v1 + v2 as f64))),
-                        (left, right) => Err(Located::new(format!("cannot perform binary operator {op:?} on {} with {}", Type::from(left), Type::from(right)), pos))
+                        (Value::Duration(v1), Value::Duration(v2)) => time_result(v1.checked_add(v2).map(Value::Duration), &pos),
+                        (Value::Date(date), Value::Duration(v)) | (Value::Duration(v), Value::Date(date)) => time_result(date.timestamp.checked_add(v).and_then(|timestamp| Date::checked(timestamp, date.offset)).map(Value::Date), &pos),
+                        (left, right) => Err(Located::new(format!("cannot perform binary operator {op:?} on {} with {}", Type::from(left), Type::from(right)).into(), pos))
                     }
                     BinaryOperator::Sub => match (left, right) {
                         (Value::Int(v1), Value::Int(v2)) => Ok(Some(Value::Int(v1 - v2))),
                         (Value::Float(v1), Value::Float(v2)) => Ok(Some(Value::Float(v1 - v2))),
                         (Value::Int(v1), Value::Float(v2)) => Ok(Some(Value::Float(v1 as f64 - v2))),
                         (Value::Float(v1), Value::Int(v2)) => Ok(Some(Value::Float(v1 - v2 as f64))),
-                        (left, right) => Err(Located::new(format!("cannot perform binary operator {op:?} on {} with {}", Type::from(left), Type::from(right)), pos))
+                        (Value::Set(v1), Value::Set(v2)) => Ok(Some(Value::Set(&v1 - &v2))),
+                        (Value::Duration(v1), Value::Duration(v2)) => time_result(v1.checked_sub(v2).map(Value::Duration), &pos),
+                        (Value::Date(date), Value::Duration(v)) => time_result(date.timestamp.checked_sub(v).and_then(|timestamp| Date::checked(timestamp, date.offset)).map(Value::Date), &pos),
+                        (Value::Date(v1), Value::Date(v2)) => time_result(v1.timestamp.checked_sub(v2.timestamp).map(Value::Duration), &pos),
+                        (left, right) => Err(Located::new(format!("cannot perform binary operator {op:?} on {} with {}", Type::from(left), Type::from(right)).into(), pos))
                     }
                     BinaryOperator::Mul => match (left, right) {
                         (Value::Int(v1), Value::Int(v2)) => Ok(Some(Value::Int(v1 * v2))),
                         (Value::Float(v1), Value::Float(v2)) => Ok(Some(Value::Float(v1 * v2))),
                         (Value::Int(v1), Value::Float(v2)) => Ok(Some(Value::Float(v1 as f64 * v2))),
                         (Value::Float(v1), Value::Int(v2)) => Ok(Some(Value::Float(v1 * v2 as f64))),
-                        (left, right) => Err(Located::new(format!("cannot perform binary operator {op:?} on {} with {}", Type::from(left), Type::from(right)), pos))
+                        (Value::Duration(v1), Value::Int(v2)) | (Value::Int(v2), Value::Duration(v1)) => time_result(v1.checked_mul(v2).map(Value::Duration), &pos),
+                        (Value::Duration(v1), Value::Float(v2)) | (Value::Float(v2), Value::Duration(v1)) => time_result(scale(v1, v2).map(Value::Duration), &pos),
+                        (left, right) => Err(Located::new(format!("cannot perform binary operator {op:?} on {} with {}", Type::from(left), Type::from(right)).into(), pos))
                     }
                     BinaryOperator::Div => match (left, right) {
                         (Value::Int(v1), Value::Int(v2)) => Ok(Some(Value::Float(v1 as f64 / v2 as f64))),
                         (Value::Float(v1), Value::Float(v2)) => Ok(Some(Value::Float(v1 / v2))),
                         (Value::Int(v1), Value::Float(v2)) => Ok(Some(Value::Float(v1 as f64 / v2))),
                         (Value::Float(v1), Value::Int(v2)) => Ok(Some(Value::Float(v1 / v2 as f64))),
-                        (left, right) => Err(Located::new(format!("cannot perform binary operator {op:?} on {} with {}", Type::from(left), Type::from(right)), pos))
+                        (Value::Duration(v1), Value::Duration(v2)) => Ok(Some(Value::Float(v1 as f64 / v2 as f64))),
+                        (Value::Duration(v1), Value::Int(v2)) => time_result(scale(v1, 1. / v2 as f64).map(Value::Duration), &pos),
+                        (Value::Duration(v1), Value::Float(v2)) => time_result(scale(v1, 1. / v2).map(Value::Duration), &pos),
+                        (left, right) => Err(Located::new(format!("cannot perform binary operator {op:?} on {} with {}", Type::from(left), Type::from(right)).into(), pos))
                     }
                     BinaryOperator::Pow => match (left, right) {
                         (Value::Int(v1), Value::Int(v2)) => Ok(Some(Value::Float((v1 as f64).powf(v2 as f64)))),
                         (Value::Float(v1), Value::Float(v2)) => Ok(Some(Value::Float(v1.powf(v2)))),
                         (Value::Int(v1), Value::Float(v2)) => Ok(Some(Value::Float((v1 as f64).powf(v2)))),
                         (Value::Float(v1), Value::Int(v2)) => Ok(Some(Value::Float(v1.powf(v2 as f64)))),
-                        (left, right) => Err(Located::new(format!("cannot perform binary operator {op:?} on {} with {}", Type::from(left), Type::from(right)), pos))
+                        (Value::Set(v1), Value::Set(v2)) => Ok(Some(Value::Set(&v1 ^ &v2))),
+                        (left, right) => Err(Located::new(format!("cannot perform binary operator {op:?} on {} with {}", Type::from(left), Type::from(right)).into(), pos))
                     }
                     BinaryOperator::Mod => match (left, right) {
                         (Value::Int(v1), Value::Int(v2)) => Ok(Some(Value::Int(v1 % v2))),
                         (Value::Float(v1), Value::Float(v2)) => Ok(Some(Value::Float(v1 % v2))),
                         (Value::Int(v1), Value::Float(v2)) => Ok(Some(Value::Float(v1 as f64 % v2))),
                         (Value::Float(v1), Value::Int(v2)) => Ok(Some(Value::Float(v1 % v2 as f64))),
-                        (left, right) => Err(Located::new(format!("cannot perform binary operator {op:?} on {} with {}", Type::from(left), Type::from(right)), pos))
+                        (left, right) => Err(Located::new(format!("cannot perform binary operator {op:?} on {} with {}", Type::from(left), Type::from(right)).into(), pos))
+                    }
+                    BinaryOperator::BitOr => match (left, right) {
+                        (Value::Int(v1), Value::Int(v2)) => Ok(Some(Value::Int(v1 | v2))),
+                        (Value::Boolean(v1), Value::Boolean(v2)) => Ok(Some(Value::Boolean(v1 | v2))),
+                        (Value::Set(v1), Value::Set(v2)) => Ok(Some(Value::Set(&v1 | &v2))),
+                        (left, right) => Err(Located::new(format!("cannot perform binary operator {op:?} on {} with {}", Type::from(left), Type::from(right)).into(), pos))
+                    }
+                    BinaryOperator::BitXor => match (left, right) {
+                        (Value::Int(v1), Value::Int(v2)) => Ok(Some(Value::Int(v1 ^ v2))),
+                        (Value::Boolean(v1), Value::Boolean(v2)) => Ok(Some(Value::Boolean(v1 ^ v2))),
+                        (Value::Set(v1), Value::Set(v2)) => Ok(Some(Value::Set(&v1 ^ &v2))),
+                        (left, right) => Err(Located::new(format!("cannot perform binary operator {op:?} on {} with {}", Type::from(left), Type::from(right)).into(), pos))
+                    }
+                    BinaryOperator::BitAnd => match (left, right) {
+                        (Value::Int(v1), Value::Int(v2)) => Ok(Some(Value::Int(v1 & v2))),
+                        (Value::Boolean(v1), Value::Boolean(v2)) => Ok(Some(Value::Boolean(v1 & v2))),
+                        (Value::Set(v1), Value::Set(v2)) => Ok(Some(Value::Set(&v1 & &v2))),
+                        (left, right) => Err(Located::new(format!("cannot perform binary operator {op:?} on {} with {}", Type::from(left), Type::from(right)).into(), pos))
+                    }
+                    BinaryOperator::Shl | BinaryOperator::Shr => match (left, right) {
+                        (Value::Int(v1), Value::Int(v2)) => {
+                            let shifted = u32::try_from(v2).ok().and_then(|v2| if op == BinaryOperator::Shl {
+                                v1.checked_shl(v2)
+                            } else {
+                                v1.checked_shr(v2)
+                            });
+                            match shifted {
+                                Some(v) => Ok(Some(Value::Int(v))),
+                                None => Err(Located::new(format!("shift amount {v2} out of range 0..64").into(), right_pos))
+                            }
+                        }
+                        (left, right) => Err(Located::new(format!("cannot perform binary operator {op:?} on {} with {}", Type::from(left), Type::from(right)).into(), pos))
+                    }
+                    BinaryOperator::Coalesce => Ok(Some(left)),
+                    BinaryOperator::Pipe => unreachable!("pipes are desugared into calls by the parser"),
+                    BinaryOperator::In => match (left, right) {
+                        (left, Value::Set(values)) => Ok(Some(Value::Boolean(values.contains(&left)))),
+                        (left, Value::Vector(values)) => Ok(Some(Value::Boolean(values.contains(&left)))),
+                        (left, Value::Map(map)) => Ok(Some(Value::Boolean(map.contains_key(&left)))),
+                        (Value::String(v1), Value::String(v2)) => Ok(Some(Value::Boolean(v2.contains(&v1)))),
+                        (left, right) => Err(Located::new(format!("cannot perform binary operator {op:?} on {} with {}", Type::from(left), Type::from(right)).into(), pos))
                     }
                 }
             }
@@ -156,11 +681,19 @@ impl Evaluate<Value, Program> for Located<Expression> {
                     UnaryOperator::Neg => match right {
                         Value::Int(v) => Ok(Some(Value::Int(-v))),
                         Value::Float(v) => Ok(Some(Value::Float(-v))),
-                        right => Err(Located::new(format!("cannot perform unary operator {op:?} on {}", Type::from(right)), pos))
+                        Value::Duration(v) => match v.checked_neg() {
+                            Some(v) => Ok(Some(Value::Duration(v))),
+                            None => Err(Located::new("duration overflow".into(), pos))
+                        }
+                        right => Err(Located::new(format!("cannot perform unary operator {op:?} on {}", Type::from(right)).into(), pos))
+                    }
+                    UnaryOperator::BitNot => match right {
+                        Value::Int(v) => Ok(Some(Value::Int(!v))),
+                        right => Err(Located::new(format!("cannot perform unary operator {op:?} on {}", Type::from(right)).into(), pos))
                     }
                     UnaryOperator::Not => match right {
                         Value::Boolean(v) => Ok(Some(Value::Boolean(!v))),
-                        right => Err(Located::new(format!("cannot perform unary operator {op:?} on {}", Type::from(right)), pos))
+                        right => Err(Located::new(format!("cannot perform unary operator {op:?} on {}", Type::from(right)).into(), pos))
                     }
                 }
             }
@@ -178,14 +711,140 @@ impl Evaluate<Value, Program> for Located<Expression> {
                         return Err(Located::new("return value is none".into(), arg_pos))
                     }
                 }
-                match head {
-                    Value::Function(addr) => {
-                        let native_fn = program.native_fns.get(addr).unwrap();
-                        native_fn(value_args, program).map_err(|err| Located::new(err, pos))
+                call(head, value_args, program, pos)
+            }
+            Expression::Try { body, catch, finally } => {
+                let mut result = body.evaluate(program);
+                if let Some((ident, block)) = catch {
+                    if let Err(err @ Located { value: Error::User(_) | Error::Runtime(_), pos: _ }) = result {
+                        let err = error_object(err, program);
+                        let scope = program.scope;
+                        let mark = program.push_scope();
+                        program.set(&ident.value, err);
+                        result = block.evaluate(program);
+                        program.leave_scope(scope, mark);
                     }
-                    head => Err(Located::new(format!("cannot call {}", Type::from(head)), pos))
                 }
+                if let Some(block) = finally {
+                    block.evaluate(program)?;
+                }
+                result
             }
+            Expression::Import { name, path } => {
+                let Located { value: path, pos: path_pos } = path;
+                let module = import(&path, program).map_err(|err| Located::new(err, path_pos))?;
+                let ident = match name {
+                    Some(name) => name.value,
+                    None => Path::new(&path).file_stem().unwrap_or_default().to_string_lossy().to_string()
+                };
+                program.define(&ident, module.clone()).map_err(|err| Located::new(err, pos))?;
+                Ok(Some(module))
+            }
+            Expression::Let { ident, annotation, expr, constant } => {
+                let expr_pos = expr.pos.clone();
+                let Some(value) = expr.evaluate(program)? else {
+                    return Err(Located::new("return value is none".into(), expr_pos))
+                };
+                if constant {
+                    program.define_const(&ident.value, value.clone())
+                } else {
+                    program.define(&ident.value, value.clone())
+                }.map_err(|err| Located::new(err, ident.pos))?;
+                program.annotate(&ident.value, annotation.map(|annotation| annotation.value));
+                Ok(Some(value))
+            }
+            Expression::Assign { target, expr } => {
+                let expr_pos = expr.pos.clone();
+                let Some(value) = expr.evaluate(program)? else {
+                    return Err(Located::new("return value is none".into(), expr_pos))
+                };
+                match target.value {
+                    Atom::Ident(ident) => {
+                        program.assign(&ident, value.clone()).map_err(|err| Located::new(err, target.pos))?;
+                    }
+                    Atom::Field { head, field, optional: _ } => {
+                        let head_pos = head.pos.clone();
+                        let Some(head) = head.evaluate(program)? else {
+                            return Err(Located::new("return value is none".into(), head_pos))
+                        };
+                        let Value::Object(addr) = head else {
+                            return Err(Located::new(format!("cannot set field of {}", Type::from(head)).into(), target.pos))
+                        };
+                        let obj = program.objects.get_mut(addr).unwrap();
+                        if obj.frozen {
+                            return Err(Located::new(format!("cannot set field {:?} of frozen object", field.value).into(), target.pos))
+                        }
+                        obj.set(&field.value, value.clone());
+                    }
+                    index @ Atom::Index { .. } => {
+                        assign_index(Located::new(index, target.pos), value.clone(), program)?;
+                    }
+                    _ => return Err(Located::new("cannot assign to this expression".into(), target.pos))
+                }
+                Ok(Some(value))
+            }
+            Expression::Function { params, ret, body, generator } => {
+                let signature = Annotation::Function(
+                    params.iter().map(|(_, annotation)| annotation.as_ref().map(|annotation| annotation.value.clone()).unwrap_or(Annotation::Any)).collect(),
+                    Box::new(if generator {
+                        Annotation::Type(Type::Iterator)
+                    } else {
+                        ret.map(|ret| ret.value).unwrap_or(Annotation::Any)
+                    })
+                );
+                let params = params.into_iter().map(|(param, _)| param).collect();
+                let scope = program.capture();
+                let addr = program.fns.create(Function { params, body, signature, scope, generator });
+                Ok(Some(Value::Closure(addr)))
+            }
+            Expression::For { ident, iter, body } => {
+                let iter_pos = iter.pos.clone();
+                let Some(iter) = iter.evaluate(program)? else {
+                    return Err(Located::new("return value is none".into(), iter_pos))
+                };
+                let iter = iterate(iter, program).map_err(|err| Located::new(err, iter_pos.clone()))?;
+                let scope = program.scope;
+                while let Some(value) = next(iter, program, &iter_pos)? {
+                    let mark = program.push_scope();
+                    program.set(&ident.value, value);
+                    let result = body.clone().evaluate(program);
+                    program.leave_scope(scope, mark);
+                    result?;
+                }
+                Ok(None)
+            }
+            Expression::Yield(_) => Err(Located::new("yield outside of generator".into(), pos)),
+        }
+    }
+}
+
+impl Evaluate<Value, Program, Error> for Located<Block> {
+    fn evaluate(self, program: &mut Program) -> Result<Option<Value>, Located<Error>> {
+        let Located { value: Block(exprs), pos } = self;
+        let scope = program.scope;
+        let mark = program.push_scope();
+        let mut value = None;
+        for expr in exprs {
+            value = match expr.evaluate(program) {
+                Ok(value) => value,
+                Err(err) => {
+                    program.leave_scope(scope, mark);
+                    return Err(err)
+                }
+            };
+        }
+        program.leave_scope(scope, mark);
+        Ok(value)
+    }
+}
+
+impl Evaluate<Value, Program, Error> for Located<Chunk> {
+    fn evaluate(self, program: &mut Program) -> Result<Option<Value>, Located<Error>> {
+        let Located { value: Chunk(exprs), pos } = self;
+        let mut value = None;
+        for expr in exprs {
+            value = expr.evaluate(program)?;
         }
+        Ok(value)
     }
 }
\ No newline at end of file
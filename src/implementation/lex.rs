@@ -1,7 +1,8 @@
 use crate::{
     structure::{
         position::Located,
-        tokens::*
+        tokens::*,
+        time
     },
     tools::lex::*
 };
@@ -11,11 +12,73 @@ pub fn lex(input: String) -> Result<Vec<Located<Token>>, Located<String>> {
 }
 
 impl Token {
+    pub fn number(lexer: &mut Lexer<Self>) -> Option<Result<Located<Self>, Located<String>>> {
+        let (mut number, mut pos) = lexer.collect_while(&|c| c.is_ascii_digit())?;
+        if lexer.get() == Some('.') {
+            number.push('.');
+            pos.extend(&lexer.pos());
+            lexer.advance();
+            if let Some((decimal, other_pos)) = lexer.collect_while(&|c| c.is_ascii_digit()) {
+                number.push_str(&decimal);
+                pos.extend(&other_pos);
+            }
+            match number.parse() {
+                Ok(number) => Some(Ok(Located::new(Self::Float(number), pos))),
+                Err(err) => Some(Err(Located::new(err.to_string(), pos)))
+            }
+        } else {
+            match number.parse() {
+                Ok(number) => Some(Ok(Located::new(Self::Int(number), pos))),
+                Err(err) => Some(Err(Located::new(err.to_string(), pos)))
+            }
+        }
+    }
+    pub fn duration(lexer: &mut Lexer<Self>, number: Located<Self>) -> Result<Located<Self>, Located<String>> {
+        let Located { value: mut number, mut pos } = number;
+        let mut duration = 0i64;
+        loop {
+            let Some((suffix, suffix_pos)) = lexer.collect_while(&|c| c.is_alphabetic()) else {
+                return Err(Located::new("expected duration unit after number".into(), lexer.pos()))
+            };
+            let Some(unit) = time::unit(&suffix) else {
+                return Err(Located::new(format!("unknown duration unit {suffix:?}"), suffix_pos))
+            };
+            pos.extend(&suffix_pos);
+            let part = match number {
+                Self::Int(v) => v.checked_mul(unit),
+                Self::Float(v) => Some((v * unit as f64).round()).filter(|v| v.abs() < i64::MAX as f64).map(|v| v as i64),
+                _ => None
+            };
+            let Some(total) = part.and_then(|part| duration.checked_add(part)) else {
+                return Err(Located::new("duration out of range".into(), pos))
+            };
+            duration = total;
+            let Some(next) = Self::number(lexer) else {
+                break
+            };
+            let next = next?;
+            pos.extend(&next.pos);
+            number = next.value;
+        }
+        Ok(Located::new(Self::Duration(duration), pos))
+    }
     pub fn ident(ident: String) -> Self {
         match ident.as_str() {
             "true" => Self::Boolean(true),
             "false" => Self::Boolean(false),
             "none" => Self::None,
+            "try" => Self::Try,
+            "catch" => Self::Catch,
+            "finally" => Self::Finally,
+            "import" => Self::Import,
+            "from" => Self::From,
+            "let" => Self::Let,
+            "const" => Self::Const,
+            "fn" => Self::Fn,
+            "for" => Self::For,
+            "in" => Self::In,
+            "yield" => Self::Yield,
+            "xor" => Self::Xor,
             _ => Self::Ident(ident)
         }
     }
@@ -32,39 +95,82 @@ impl Lexable for Token {
             '{' => { lexer.advance(); Ok(Some(Located::new(Self::ObjIn, pos))) }
             '}' => { lexer.advance(); Ok(Some(Located::new(Self::ObjOut, pos))) }
             '+' => { lexer.advance(); Ok(Some(Located::new(Self::Add, pos))) }
-            '-' => { lexer.advance(); Ok(Some(Located::new(Self::Sub, pos))) }
+            '-' => {
+                lexer.advance();
+                if lexer.get() == Some('>') {
+                    let mut pos = pos;
+                    pos.extend(&lexer.pos());
+                    lexer.advance();
+                    Ok(Some(Located::new(Self::Arrow, pos)))
+                } else {
+                    Ok(Some(Located::new(Self::Sub, pos)))
+                }
+            }
             '*' => { lexer.advance(); Ok(Some(Located::new(Self::Mul, pos))) }
             '/' => { lexer.advance(); Ok(Some(Located::new(Self::Div, pos))) }
             '^' => { lexer.advance(); Ok(Some(Located::new(Self::Pow, pos))) }
             '%' => { lexer.advance(); Ok(Some(Located::new(Self::Mod, pos))) }
+            '|' => {
+                lexer.advance();
+                if lexer.get() == Some('>') {
+                    let mut pos = pos;
+                    pos.extend(&lexer.pos());
+                    lexer.advance();
+                    Ok(Some(Located::new(Self::Pipe, pos)))
+                } else {
+                    Ok(Some(Located::new(Self::BitOr, pos)))
+                }
+            }
+            '?' => {
+                lexer.advance();
+                let token = match lexer.get() {
+                    Some('.') => Self::OptField,
+                    Some('?') => Self::Coalesce,
+                    _ => return Err(Located::new(format!("bad character {:?}", '?'), pos))
+                };
+                let mut pos = pos;
+                pos.extend(&lexer.pos());
+                lexer.advance();
+                Ok(Some(Located::new(token, pos)))
+            }
+            '&' => { lexer.advance(); Ok(Some(Located::new(Self::BitAnd, pos))) }
+            '~' => { lexer.advance(); Ok(Some(Located::new(Self::BitXor, pos))) }
+            '<' | '>' => {
+                lexer.advance();
+                if lexer.get() != Some(c) {
+                    return Err(Located::new(format!("bad character {c:?}"), pos))
+                }
+                let mut pos = pos;
+                pos.extend(&lexer.pos());
+                lexer.advance();
+                Ok(Some(Located::new(if c == '<' { Self::Shl } else { Self::Shr }, pos)))
+            }
             '.' => { lexer.advance(); Ok(Some(Located::new(Self::Field, pos))) }
             ',' => { lexer.advance(); Ok(Some(Located::new(Self::Seperate, pos))) }
+            ';' => { lexer.advance(); Ok(Some(Located::new(Self::End, pos))) }
+            '=' => { lexer.advance(); Ok(Some(Located::new(Self::Assign, pos))) }
+            ':' => { lexer.advance(); Ok(Some(Located::new(Self::Colon, pos))) }
             _ => if let Some(res) = lexer.delimit('"', '"', Some('\\')) {
                 let (string, pos) = res?;
                 Ok(Some(Located::new(Self::String(string), pos)))
             } else if let Some(res) = lexer.delimit('\'', '\'', Some('\\')) {
                 let (string, pos) = res?;
                 Ok(Some(Located::new(Self::String(string), pos)))
-            } else if let Some((mut number, mut pos)) = lexer.collect_while(&|c| c.is_ascii_digit()) {
-                if lexer.get() == Some('.') {
-                    number.push('.');
-                    pos.extend(&lexer.pos());
-                    lexer.advance();
-                    if let Some((decimal, other_pos)) = lexer.collect_while(&|c| c.is_ascii_digit()) {
-                        number.push_str(&decimal);
-                        pos.extend(&other_pos);
-                    }
-                    match number.parse() {
-                        Ok(number) => Ok(Some(Located::new(Self::Float(number), pos))),
-                        Err(err) => Err(Located::new(err.to_string(), pos))
-                    }
-                } else {
-                    match number.parse() {
-                        Ok(number) => Ok(Some(Located::new(Self::Int(number), pos))),
-                        Err(err) => Err(Located::new(err.to_string(), pos))
-                    }
+            } else if let Some(res) = Self::number(lexer) {
+                let number = res?;
+                if !lexer.check(|c| c.is_alphabetic()) {
+                    return Ok(Some(number))
                 }
+                Self::duration(lexer, number).map(Some)
             } else if let Some((ident, pos)) = lexer.collect_while(&|c| c.is_alphanumeric() || c == '_') {
+                if ident == "r" {
+                    if let Some(res) = lexer.delimit('"', '"', Some('\\')) {
+                        let (pattern, end_pos) = res?;
+                        let mut pos = pos;
+                        pos.extend(&end_pos);
+                        return Ok(Some(Located::new(Self::Regex(pattern), pos)))
+                    }
+                }
                 Ok(Some(Located::new(Self::ident(ident), pos)))
             } else {
                 Err(Located::new(format!("bad character {:?}", lexer.get().unwrap_or_default()), lexer.pos()))
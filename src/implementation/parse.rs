@@ -13,7 +13,8 @@ use crate::{
             Position,
             Located
         },
-        ast::*
+        ast::*,
+        value::Type
     }
 };
 
@@ -33,25 +34,45 @@ impl Atom {
     pub fn path(parser: &mut Parser<Token>) -> ParseResult<Self> {
         let mut head = Self::parse(parser)?;
         while let Some(Located { value: token, pos }) = parser.peek() {
-            match token {
-                Token::Field => {
+            let optional = token == &Token::OptField;
+            if optional {
+                parser.expect()?;
+            }
+            match parser.peek() {
+                Some(Located { value: Token::ArrayIn, pos: _ }) => {
+                    parser.expect()?;
+                    let field = Expression::parse(parser)?;
+                    let Located { value: _, pos: end_pos } = parser.expect_token(Token::ArrayOut)?;
+                    let pos = Position::between(&head.pos, &end_pos);
+                    head = Located::new(Self::Index {
+                        head: Box::new(head),
+                        field: Box::new(field),
+                        optional
+                    }, pos)
+                }
+                Some(Located { value: Token::Field, pos: _ }) if !optional => {
                     parser.expect()?;
                     let field = Self::ident(parser)?;
                     let pos = Position::between(&head.pos, &field.pos);
                     head = Located::new(Self::Field {
                         head: Box::new(head),
-                        field
+                        field,
+                        optional
                     }, pos)
                 }
-                Token::ArrayIn => {
-                    parser.expect()?;
-                    let field = Expression::parse(parser)?;
+                Some(Located { value: Token::Ident(_), pos: _ }) if optional => {
+                    let field = Self::ident(parser)?;
                     let pos = Position::between(&head.pos, &field.pos);
-                    head = Located::new(Self::Index {
+                    head = Located::new(Self::Field {
                         head: Box::new(head),
-                        field: Box::new(field)
+                        field,
+                        optional
                     }, pos)
                 }
+                _ if optional => {
+                    let Located { value: token, pos } = parser.expect()?;
+                    return Err(Located::new(format!("expected ident token or {:?}, got token {token:?}", Token::ArrayIn), pos))
+                }
                 _ => break
             }
         }
@@ -63,10 +84,12 @@ impl Parsable<Token> for Atom {
         let Located { value: token, mut pos } = parser.expect()?;
         match token {
             Token::Int(v) => Ok(Located::new(Self::Int(v), pos)),
+            Token::Duration(v) => Ok(Located::new(Self::Duration(v), pos)),
             Token::Float(v) => Ok(Located::new(Self::Float(v), pos)),
             Token::Boolean(v) => Ok(Located::new(Self::Boolean(v), pos)),
             Token::None => Ok(Located::new(Self::None, pos)),
             Token::String(v) => Ok(Located::new(Self::String(v), pos)),
+            Token::Regex(v) => Ok(Located::new(Self::Regex(v), pos)),
             Token::Ident(v) => Ok(Located::new(Self::Ident(v), pos)),
             Token::ExprIn => {
                 let expr = Expression::parse(parser)?;
@@ -96,6 +119,85 @@ impl Parsable<Token> for Atom {
                 }
                 Err(Located::new("unclosed vector".into(), pos))
             }
+            Token::ObjIn => {
+                let mut entries = vec![];
+                let mut values = vec![];
+                while let Some(Located { value: token, pos: _ }) = parser.peek() {
+                    if token == &Token::ObjOut {
+                        let Located { value: _, pos: end_pos } = parser.expect()?;
+                        pos.extend(&end_pos);
+                        if values.is_empty() {
+                            return Ok(Located::new(Self::Map(entries), pos));
+                        }
+                        return Ok(Located::new(Self::Set(values), pos));
+                    }
+                    let key = Expression::parse(parser)?;
+                    let is_set = match parser.peek() {
+                        Some(Located { value: Token::Colon, pos: _ }) => !values.is_empty(),
+                        _ => entries.is_empty()
+                    };
+                    if is_set {
+                        values.push(key);
+                    } else {
+                        parser.expect_token(Token::Colon)?;
+                        let value = Expression::parse(parser)?;
+                        entries.push((key, value));
+                    }
+                    if let Some(Located { value: Token::ObjOut, pos: _ }) = parser.peek() {
+                        continue;
+                    }
+                    if let Some(Located { value: token, pos }) = parser.get() {
+                        if token != Token::Seperate {
+                            return Err(Located::new(format!("expected token {:?} or {:?}, got token {token:?}", Token::Seperate, Token::ObjOut), pos))
+                        }
+                    }
+                }
+                Err(Located::new("unclosed map".into(), pos))
+            }
+            Token::Try => {
+                let expr = Expression::try_catch(parser, pos)?;
+                let pos = expr.pos.clone();
+                Ok(Located::new(Self::Expression(Box::new(expr)), pos))
+            }
+            Token::Import => {
+                let expr = Expression::import(parser, pos)?;
+                let pos = expr.pos.clone();
+                Ok(Located::new(Self::Expression(Box::new(expr)), pos))
+            }
+            Token::Let | Token::Const => {
+                let constant = token == Token::Const;
+                let ident = Self::ident(parser)?;
+                let mut annotation = None;
+                if let Some(Located { value: Token::Colon, pos: _ }) = parser.peek() {
+                    parser.expect()?;
+                    annotation = Some(Annotation::parse(parser)?);
+                }
+                parser.expect_token(Token::Assign)?;
+                let expr = Expression::parse(parser)?;
+                pos.extend(&expr.pos);
+                let expr = Located::new(Expression::Let { ident, annotation, expr: Box::new(expr), constant }, pos.clone());
+                Ok(Located::new(Self::Expression(Box::new(expr)), pos))
+            }
+            Token::For => {
+                let ident = Self::ident(parser)?;
+                parser.expect_token(Token::In)?;
+                let iter = Expression::parse(parser)?;
+                let body = Block::parse(parser)?;
+                pos.extend(&body.pos);
+                let expr = Located::new(Expression::For { ident, iter: Box::new(iter), body }, pos.clone());
+                Ok(Located::new(Self::Expression(Box::new(expr)), pos))
+            }
+            Token::Yield => {
+                let expr = Expression::parse(parser)?;
+                pos.extend(&expr.pos);
+                let expr = Located::new(Expression::Yield(Box::new(expr)), pos.clone());
+                Ok(Located::new(Self::Expression(Box::new(expr)), pos))
+            }
+            Token::Fn => {
+                let expr = Expression::function(parser, pos)?;
+                let pos = expr.pos.clone();
+                Ok(Located::new(Self::Expression(Box::new(expr)), pos))
+            }
             token => Err(Located::new(format!("unexpected token: {token:?}"), pos))
         }
     }
@@ -128,7 +230,175 @@ impl Parsable<Token> for Args {
     }
 }
 
+impl Annotation {
+    pub fn single(parser: &mut Parser<Token>) -> ParseResult<Self> {
+        let Located { value: token, mut pos } = parser.expect()?;
+        match token {
+            Token::None => Ok(Located::new(Self::None, pos)),
+            Token::Ident(ident) => match ident.as_str() {
+                "any" => Ok(Located::new(Self::Any, pos)),
+                "int" => Ok(Located::new(Self::Type(Type::Int), pos)),
+                "float" => Ok(Located::new(Self::Type(Type::Float), pos)),
+                "bool" | "boolean" => Ok(Located::new(Self::Type(Type::Boolean), pos)),
+                "string" => Ok(Located::new(Self::Type(Type::String), pos)),
+                "vector" => Ok(Located::new(Self::Type(Type::Vector), pos)),
+                "map" => Ok(Located::new(Self::Type(Type::Map), pos)),
+                "set" => Ok(Located::new(Self::Type(Type::Set), pos)),
+                "object" => Ok(Located::new(Self::Type(Type::Object), pos)),
+                "function" => Ok(Located::new(Self::Type(Type::Function), pos)),
+                "iterator" => Ok(Located::new(Self::Type(Type::Iterator), pos)),
+                "date" => Ok(Located::new(Self::Type(Type::Date), pos)),
+                "duration" => Ok(Located::new(Self::Type(Type::Duration), pos)),
+                "regex" => Ok(Located::new(Self::Type(Type::Regex), pos)),
+                _ => Err(Located::new(format!("unknown type {ident:?}"), pos))
+            }
+            Token::ArrayIn => {
+                let typ = Self::parse(parser)?;
+                let Located { value: _, pos: end_pos } = parser.expect_token(Token::ArrayOut)?;
+                pos.extend(&end_pos);
+                Ok(Located::new(Self::Vector(Box::new(typ.value)), pos))
+            }
+            Token::ExprIn => {
+                let typ = Self::parse(parser)?;
+                let Located { value: _, pos: end_pos } = parser.expect_token(Token::ExprOut)?;
+                pos.extend(&end_pos);
+                Ok(Located::new(typ.value, pos))
+            }
+            Token::Fn => {
+                parser.expect_token(Token::ExprIn)?;
+                let mut params = vec![];
+                if let Some(Located { value: Token::ExprOut, pos: _ }) = parser.peek() {
+                    let Located { value: _, pos: end_pos } = parser.expect()?;
+                    pos.extend(&end_pos);
+                } else {
+                    loop {
+                        params.push(Self::parse(parser)?.value);
+                        let Located { value: token, pos: end_pos } = parser.expect()?;
+                        match token {
+                            Token::ExprOut => {
+                                pos.extend(&end_pos);
+                                break
+                            }
+                            Token::Seperate => continue,
+                            token => return Err(Located::new(format!("expected token {:?} or {:?}, got token {token:?}", Token::Seperate, Token::ExprOut), end_pos))
+                        }
+                    }
+                }
+                let mut ret = Self::Any;
+                if let Some(Located { value: Token::Arrow, pos: _ }) = parser.peek() {
+                    parser.expect()?;
+                    let typ = Self::single(parser)?;
+                    pos.extend(&typ.pos);
+                    ret = typ.value;
+                }
+                Ok(Located::new(Self::Function(params, Box::new(ret)), pos))
+            }
+            token => Err(Located::new(format!("expected type, got token {token:?}"), pos))
+        }
+    }
+}
+impl Parsable<Token> for Annotation {
+    fn parse(parser: &mut Parser<Token>) -> ParseResult<Self> {
+        let first = Self::single(parser)?;
+        let mut pos = first.pos.clone();
+        let mut types = vec![first.value];
+        while let Some(Located { value: Token::BitOr, pos: _ }) = parser.peek() {
+            parser.expect()?;
+            let typ = Self::single(parser)?;
+            pos.extend(&typ.pos);
+            types.push(typ.value);
+        }
+        if types.len() == 1 {
+            return Ok(Located::new(types.remove(0), pos))
+        }
+        Ok(Located::new(Self::Union(types), pos))
+    }
+}
+
+impl Parsable<Token> for Block {
+    fn parse(parser: &mut Parser<Token>) -> ParseResult<Self> {
+        let Located { value: _, mut pos } = parser.expect_token(Token::ObjIn)?;
+        let mut exprs = vec![];
+        while let Some(Located { value: token, pos: _ }) = parser.peek() {
+            if token == &Token::ObjOut {
+                let Located { value: _, pos: end_pos } = parser.expect()?;
+                pos.extend(&end_pos);
+                return Ok(Located::new(Self(exprs), pos));
+            }
+            let expr = Expression::parse(parser)?;
+            exprs.push(expr);
+            if let Some(Located { value: Token::ObjOut, pos: _ }) = parser.peek() {
+                continue;
+            }
+            if let Some(Located { value: token, pos }) = parser.get() {
+                if token != Token::End {
+                    return Err(Located::new(format!("expected token {:?} or {:?}, got token {token:?}", Token::End, Token::ObjOut), pos))
+                }
+            }
+        }
+        Err(Located::new("unclosed block".into(), pos))
+    }
+}
+
+impl Parsable<Token> for Chunk {
+    fn parse(parser: &mut Parser<Token>) -> ParseResult<Self> {
+        let mut pos = parser.peek().map(|token| token.pos.clone()).unwrap_or_default();
+        let mut exprs = vec![];
+        while parser.peek().is_some() {
+            let expr = Expression::parse(parser)?;
+            pos.extend(&expr.pos);
+            exprs.push(expr);
+            if let Some(Located { value: token, pos }) = parser.get() {
+                if token != Token::End {
+                    return Err(Located::new(format!("expected token {:?}, got token {token:?}", Token::End), pos))
+                }
+            }
+        }
+        Ok(Located::new(Self(exprs), pos))
+    }
+}
+
 impl Expression {
+    pub fn assign(parser: &mut Parser<Token>) -> ParseResult<Self> {
+        let left = Self::binary(parser, 0)?;
+        let Some(Located { value: Token::Assign, pos: _ }) = parser.peek() else {
+            return Ok(left)
+        };
+        parser.expect()?;
+        let expr = Self::assign(parser)?;
+        let mut pos = left.pos.clone();
+        pos.extend(&expr.pos);
+        match left.value {
+            Self::Atom(atom @ (Atom::Ident(_) | Atom::Field { optional: false, .. } | Atom::Index { optional: false, .. })) => {
+                let target = Located::new(atom, left.pos);
+                Ok(Located::new(Self::Assign { target, expr: Box::new(expr) }, pos))
+            }
+            _ => Err(Located::new("cannot assign to this expression".into(), left.pos))
+        }
+    }
+    /// desugars `left |> right` into a call of `right` with `left` as its first argument
+    pub fn pipe(left: Located<Self>, right: Located<Self>) -> Located<Self> {
+        let mut pos = left.pos.clone();
+        pos.extend(&right.pos);
+        let Located { value: right, pos: right_pos } = right;
+        match right {
+            Self::Call { head, args: Located { value: Args(mut args), pos: mut args_pos } } => {
+                args_pos.extend(&left.pos);
+                args.insert(0, left);
+                Located::new(Self::Call { head, args: Located::new(Args(args), args_pos) }, pos)
+            }
+            Self::Atom(atom) => {
+                let args_pos = left.pos.clone();
+                let head = Located::new(atom, right_pos);
+                Located::new(Self::Call { head, args: Located::new(Args(vec![left]), args_pos) }, pos)
+            }
+            right => {
+                let args_pos = left.pos.clone();
+                let head = Located::new(Atom::Expression(Box::new(Located::new(right, right_pos.clone()))), right_pos);
+                Located::new(Self::Call { head, args: Located::new(Args(vec![left]), args_pos) }, pos)
+            }
+        }
+    }
     pub fn binary(parser: &mut Parser<Token>, layer: usize) -> ParseResult<Self> {
         let Some(ops) = BinaryOperator::layer(layer) else {
             return Self::unary(parser, 0)
@@ -143,6 +413,10 @@ impl Expression {
             }
             parser.expect()?;
             let right = Self::binary(parser, layer + 1)?;
+            if op == BinaryOperator::Pipe {
+                left = Self::pipe(left, right);
+                continue;
+            }
             let mut pos = left.pos.clone();
             pos.extend(&right.pos);
             left = Located::new(Self::Binary { op, left: Box::new(left), right: Box::new(right) }, pos)
@@ -176,12 +450,100 @@ impl Expression {
             Ok(head.map(Self::Atom))
         }
     }
+    pub fn try_catch(parser: &mut Parser<Token>, mut pos: Position) -> ParseResult<Self> {
+        let body = Self::parse(parser)?;
+        pos.extend(&body.pos);
+        let mut catch = None;
+        if let Some(Located { value: Token::Catch, pos: _ }) = parser.peek() {
+            parser.expect()?;
+            let ident = Atom::ident(parser)?;
+            let block = Block::parse(parser)?;
+            pos.extend(&block.pos);
+            catch = Some((ident, block));
+        }
+        let mut finally = None;
+        if let Some(Located { value: Token::Finally, pos: _ }) = parser.peek() {
+            parser.expect()?;
+            let block = Block::parse(parser)?;
+            pos.extend(&block.pos);
+            finally = Some(block);
+        }
+        if catch.is_none() && finally.is_none() {
+            return Err(Located::new(format!("expected token {:?} or {:?} after try body", Token::Catch, Token::Finally), pos))
+        }
+        Ok(Located::new(Self::Try { body: Box::new(body), catch, finally }, pos))
+    }
+    pub fn import(parser: &mut Parser<Token>, mut pos: Position) -> ParseResult<Self> {
+        let mut name = None;
+        if let Some(Located { value: Token::Ident(_), pos: _ }) = parser.peek() {
+            name = Some(Atom::ident(parser)?);
+            parser.expect_token(Token::From)?;
+        }
+        let Located { value: token, pos: path_pos } = parser.expect()?;
+        let Token::String(path) = token else {
+            return Err(Located::new(format!("expected string token, got token {token:?}"), path_pos))
+        };
+        pos.extend(&path_pos);
+        Ok(Located::new(Self::Import { name, path: Located::new(path, path_pos) }, pos))
+    }
+    pub fn function(parser: &mut Parser<Token>, mut pos: Position) -> ParseResult<Self> {
+        parser.expect_token(Token::ExprIn)?;
+        let mut params = vec![];
+        if let Some(Located { value: Token::ExprOut, pos: _ }) = parser.peek() {
+            parser.expect()?;
+        } else {
+            loop {
+                let param = Atom::ident(parser)?;
+                let mut annotation = None;
+                if let Some(Located { value: Token::Colon, pos: _ }) = parser.peek() {
+                    parser.expect()?;
+                    annotation = Some(Annotation::parse(parser)?);
+                }
+                params.push((param, annotation));
+                let Located { value: token, pos } = parser.expect()?;
+                match token {
+                    Token::ExprOut => break,
+                    Token::Seperate => continue,
+                    token => return Err(Located::new(format!("expected token {:?} or {:?}, got token {token:?}", Token::Seperate, Token::ExprOut), pos))
+                }
+            }
+        }
+        let mut ret = None;
+        if let Some(Located { value: Token::Arrow, pos: _ }) = parser.peek() {
+            parser.expect()?;
+            ret = Some(Annotation::parse(parser)?);
+        }
+        let body = Block::parse(parser)?;
+        pos.extend(&body.pos);
+        let generator = body.value.yields();
+        if generator {
+            body.value.0.iter().try_for_each(Self::suspendable)?;
+        }
+        Ok(Located::new(Self::Function { params, ret, body, generator }, pos))
+    }
+    /// generators can only suspend at a statement: a yield, a for loop or a try body and catch block
+    fn suspendable(expr: &Located<Self>) -> Result<(), Located<String>> {
+        match &expr.value {
+            expr if !expr.yields() => Ok(()),
+            Self::Atom(Atom::Expression(expr)) => Self::suspendable(expr),
+            Self::Yield(expr) if !expr.value.yields() => Ok(()),
+            Self::For { ident: _, iter, body } if !iter.value.yields() => body.value.0.iter().try_for_each(Self::suspendable),
+            Self::Try { body, catch, finally } if !finally.as_ref().is_some_and(|block| block.value.yields()) => {
+                Self::suspendable(body)?;
+                match catch {
+                    Some((_, block)) => block.value.0.iter().try_for_each(Self::suspendable),
+                    None => Ok(())
+                }
+            }
+            _ => Err(Located::new("yield can only be used as a statement".into(), expr.pos.clone()))
+        }
+    }
     pub fn atom(parser: &mut Parser<Token>) -> ParseResult<Self> {
         Ok(Atom::parse(parser)?.map(Self::Atom))
     }
 }
 impl Parsable<Token> for Expression {
     fn parse(parser: &mut Parser<Token>) -> ParseResult<Self> {
-        Self::binary(parser, 0)
+        Self::assign(parser)
     }
 }
\ No newline at end of file
@@ -5,7 +5,9 @@ use std::{
         stdout,
         stdin
     },
-    collections::HashMap
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf}
 };
 
 use structure::value::{
@@ -13,54 +15,265 @@ use structure::value::{
     Value
 };
 use structure::program::Program;
+use stdlib::io::{Capabilities, Access};
 
 use crate::{
     implementation::{
-        evaluate::evaluate,
+        evaluate::{evaluate, import, call},
+        check::check,
         lex::lex,
         parse::parse
     },
     structure::tokens::Token
 };
 use crate::structure::{
-    position::Located,
-    ast::Expression
+    position::{Located, Position},
+    ast::Chunk,
+    error::Error
 };
 
 pub mod structure;
 pub mod tools;
 pub mod implementation;
+pub mod stdlib;
 
-pub fn eval(input: &str, program: &mut Program) -> Result<Option<Value>, Located<String>> {
+pub fn eval(input: &str, program: &mut Program) -> Result<Option<Value>, Located<Error>> {
     let tokens = lex(input.to_string())?;
-    let ast = parse::<Token, Expression>(tokens)?;
+    let ast = parse::<Token, Chunk>(tokens)?;
+    check(&ast, program).map_err(|errors| {
+        let pos = errors[0].pos.clone();
+        let msg = errors.into_iter()
+            .map(|Located { value: msg, pos }| format!("{}:{}: {msg}", pos.ln.start + 1, pos.col.start + 1))
+            .collect::<Vec<String>>()
+            .join("\n");
+        Located::new(Error::Runtime(msg), pos)
+    })?;
     evaluate(ast, program)
 }
 
-fn main() {
-    #[cfg(not(debug_assertions))]
-    std::panic::set_hook(Box::new(|_info| {}));
-    std::panic::catch_unwind(|| {
-        let mut program = Program::init();
-        let args_string = std::env::args().skip(1).collect::<Vec<String>>().join(" ");
-        if !args_string.is_empty() {
-            match eval(&args_string, &mut program) {
-                Ok(Some(value)) => println!("{value}"),
-                Err(Located { value: err, pos }) => println!("ERROR: {err}"),
-                _ => {}
+fn report(result: Result<Option<Value>, Located<Error>>) {
+    match result {
+        Ok(Some(value)) => println!("{value}"),
+        Err(Located { value: Error::Exit(code), pos: _ }) => {
+            stdout().flush();
+            std::process::exit(code)
+        }
+        Err(Located { value: err, pos }) => println!("ERROR: {err}"),
+        _ => {}
+    }
+}
+
+fn discover(path: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if !path.exists() {
+        return Err(std::io::ErrorKind::NotFound.into())
+    }
+    if path.is_dir() {
+        let mut entries = fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<PathBuf>>>()?;
+        entries.sort();
+        for entry in entries {
+            discover(&entry, files)?;
+        }
+    } else if path.extension().is_some_and(|ext| ext == "ev") {
+        files.push(path.to_path_buf());
+    }
+    Ok(())
+}
+
+fn tests(module: &Value, program: &Program) -> Vec<(String, Value)> {
+    let Value::Object(addr) = module else { return vec![] };
+    let Some(object) = program.objects.get(*addr) else { return vec![] };
+    let mut tests = object.map.iter()
+        .filter(|(name, value)| name.starts_with("test_") && matches!(value, Value::Closure(_)))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect::<Vec<(String, Value)>>();
+    tests.sort_by(|(name1, _), (name2, _)| name1.cmp(name2));
+    tests
+}
+
+/// a program that may use the console but only read the directory of the test file
+fn sandbox(file: &str) -> Program {
+    let dir = match Path::new(file).parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from(".")
+    };
+    Program::init_with(Capabilities { console: true, read: Access::Paths(vec![dir]), ..Default::default() })
+}
+
+fn run_test(file: &str, name: &str) -> Result<(), String> {
+    let mut program = sandbox(file);
+    let module = import(file, &mut program).map_err(|err| err.to_string())?;
+    let Some((_, func)) = tests(&module, &program).into_iter().find(|(test, _)| test == name) else {
+        return Err(format!("{file}: {name} is not a test function"))
+    };
+    call(func, vec![], &mut program, Position::default())
+        .map(|_| ())
+        .map_err(|Located { value: err, pos }| format!("{file}:{}:{}: {err}", pos.ln.start + 1, pos.col.start + 1))
+}
+
+fn test(paths: &[String]) -> i32 {
+    let mut files = vec![];
+    for path in paths {
+        if let Err(err) = discover(Path::new(path), &mut files) {
+            println!("ERROR: cannot read {path:?}: {err}");
+            return 1
+        }
+    }
+    let (mut passed, mut failed) = (0, 0);
+    for file in files {
+        let file = file.display().to_string();
+        let mut program = sandbox(&file);
+        let names = match import(&file, &mut program) {
+            Ok(module) => tests(&module, &program).into_iter().map(|(name, _)| name).collect::<Vec<String>>(),
+            Err(err) => {
+                println!("FAIL {file}\n  {err}");
+                failed += 1;
+                continue
             }
-            return
+        };
+        if names.is_empty() {
+            println!("PASS {file}");
+            passed += 1;
+            continue
         }
-        loop {
-            let mut input = String::new();
-            print!("> ");
-            stdout().flush();
-            stdin().read_line(&mut input);
-            match eval(&input, &mut program) {
-                Ok(Some(value)) => println!("{value}"),
-                Err(Located { value: err, pos }) => println!("ERROR: {err}"),
-                _ => {}
+        for name in names {
+            match run_test(&file, &name) {
+                Ok(()) => {
+                    println!("PASS {file}::{name}");
+                    passed += 1;
+                }
+                Err(err) => {
+                    println!("FAIL {file}::{name}\n  {err}");
+                    failed += 1;
+                }
+            }
+        }
+    }
+    println!("\n{passed} passed, {failed} failed");
+    if failed > 0 { 1 } else { 0 }
+}
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    if args.first().is_some_and(|arg| arg == "test") {
+        let paths = if args.len() > 1 { args[1..].to_vec() } else { vec![".".into()] };
+        std::process::exit(test(&paths))
+    }
+    let mut program = Program::init_with(Capabilities::all());
+    let args_string = args.join(" ");
+    if !args_string.is_empty() {
+        report(eval(&args_string, &mut program));
+        return
+    }
+    loop {
+        let mut input = String::new();
+        print!("> ");
+        stdout().flush();
+        match stdin().read_line(&mut input) {
+            Ok(0) => {
+                println!();
+                break
+            }
+            Ok(_) => match input.trim().strip_prefix(":help") {
+                Some(topic) if topic.trim().is_empty() => report(eval("help()", &mut program)),
+                Some(topic) if topic.starts_with(char::is_whitespace) => report(eval(&format!("help({topic})"), &mut program)),
+                _ => report(eval(&input, &mut program))
+            }
+            Err(err) => {
+                println!("ERROR: {err}");
+                break
             }
         }
-    });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scopes_are_reclaimed() {
+        let mut program = Program::init();
+        let scopes = program.scopes.len();
+        eval("let f = fn(x: int) -> int { x + 1 }; for i in [1, 2, 3] { let j = f(i) }", &mut program).unwrap();
+        assert_eq!(program.scopes.len(), scopes);
+        eval("let g = fn() { let y = 1; fn() { y } }; let h = g()", &mut program).unwrap();
+        let scopes = program.scopes.len();
+        eval("h(); for i in [1, 2] { g() }", &mut program).unwrap();
+        assert!(program.scopes.len() > scopes);
+        assert_eq!(eval("h()", &mut program).unwrap(), Some(Value::Int(1)));
+    }
+
+    #[test]
+    fn import_requires_read_access() {
+        let mut program = Program::init();
+        let err = import("tests/pipe.ev", &mut program).unwrap_err();
+        assert_eq!(err.to_string(), "permission denied: cannot import \"tests/pipe.ev\"");
+        let mut program = sandbox("tests/pipe.ev");
+        assert!(import("tests/pipe.ev", &mut program).is_ok());
+        assert!(import("Cargo.toml", &mut program).unwrap_err().to_string().starts_with("permission denied"));
+    }
+
+    #[test]
+    fn sandbox_allows_console() {
+        let mut program = sandbox("tests/pipe.ev");
+        assert!(eval("print(\"\")", &mut program).is_ok());
+        assert!(eval("print(\"\")", &mut Program::init()).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_rejects_dangling_symlinks() {
+        let root = std::env::temp_dir().join(format!("evalio-write-{}", std::process::id()));
+        let outside = std::env::temp_dir().join(format!("evalio-outside-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let link = root.join("link");
+        std::os::unix::fs::symlink(&outside, &link).unwrap();
+        let mut program = Program::init_with(Capabilities { write: Access::Paths(vec![root.clone()]), ..Default::default() });
+        let result = eval(&format!("write_file({:?}, \"escaped\")", link.display().to_string()), &mut program);
+        let exists = outside.exists();
+        fs::remove_dir_all(&root).unwrap();
+        let _ = fs::remove_file(&outside);
+        assert!(result.unwrap_err().value.to_string().starts_with("permission denied"));
+        assert!(!exists);
+    }
+
+    #[test]
+    fn host_closures_keep_their_state() {
+        use std::{cell::Cell, rc::Rc};
+        let mut program = Program::init();
+        let count = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&count);
+        program.new_closure("tick", &["step"], "advances the host counter by step", move |args, _| {
+            let Some(Value::Int(step)) = args.first() else {
+                return Err("expected int for argument #1".to_string().into())
+            };
+            counter.set(counter.get() + step);
+            Ok(Some(Value::Int(counter.get())))
+        });
+        assert_eq!(eval("tick(2); tick(3)", &mut program).unwrap(), Some(Value::Int(5)));
+        assert_eq!(count.get(), 5);
+        let doc = "tick(step)\n    advances the host counter by step";
+        assert_eq!(eval("help(tick)", &mut program).unwrap(), Some(Value::String(doc.into())));
+        let Some(Value::String(help)) = eval("help()", &mut program).unwrap() else {
+            panic!("help() should list the globals")
+        };
+        assert!(help.contains(doc));
+    }
+
+    #[test]
+    fn typed_natives_report_argument_errors() {
+        let mut program = Program::init();
+        program.new_typed("step", &["x", "by?"], "adds by to x, 1 by default", |x: i64, by: Option<i64>| x + by.unwrap_or(1));
+        let error = |input: &str, program: &mut Program| eval(input, program).unwrap_err().value.to_string();
+        assert_eq!(error("math.gcd(1, \"a\")", &mut program), "expected int for argument #2, got string");
+        assert_eq!(error("math.gcd(1)", &mut program), "expected 2 arguments, got 1");
+        assert_eq!(error("math.gcd(1, 2, 3)", &mut program), "expected 2 arguments, got 3");
+        assert_eq!(eval("step(1)", &mut program).unwrap(), Some(Value::Int(2)));
+        assert_eq!(eval("step(1, 5)", &mut program).unwrap(), Some(Value::Int(6)));
+        assert_eq!(error("step(1, 1.5)", &mut program), "expected int or none for argument #2, got float");
+        assert_eq!(error("step()", &mut program), "expected at least 1 arguments, got 0");
+        assert_eq!(error("step(1, 2, 3)", &mut program), "expected at most 2 arguments, got 3");
+    }
 }
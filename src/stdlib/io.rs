@@ -0,0 +1,148 @@
+use std::{
+    fs,
+    io::{prelude::*, stdin, stdout},
+    path::{Path, PathBuf}
+};
+use crate::structure::{
+    program::Program,
+    value::*,
+    error::Error
+};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Access {
+    #[default]
+    None,
+    Paths(Vec<PathBuf>),
+    All
+}
+impl Access {
+    pub fn enabled(&self) -> bool {
+        *self != Self::None
+    }
+    pub fn allows(&self, path: &Path) -> bool {
+        match self {
+            Access::None => false,
+            Access::All => true,
+            Access::Paths(roots) => roots.iter()
+                .filter_map(|root| fs::canonicalize(root).ok())
+                .any(|root| path.starts_with(root))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Capabilities {
+    pub console: bool,
+    pub read: Access,
+    pub write: Access,
+}
+impl Capabilities {
+    pub fn all() -> Self {
+        Self { console: true, read: Access::All, write: Access::All }
+    }
+}
+
+pub fn register(program: &mut Program) {
+    if program.capabilities.console {
+        program.new_fn("print", &["values..."], "writes the values to stdout separated by spaces", _print);
+        program.new_fn("println", &["values..."], "writes the values to stdout followed by a newline", _println);
+        program.new_fn("input", &["prompt?"], "reads a line from stdin", _input);
+    }
+    if program.capabilities.read.enabled() {
+        program.new_fn("read_file", &["path"], "returns the contents of a file", _read_file);
+    }
+    if program.capabilities.write.enabled() {
+        program.new_fn("write_file", &["path", "contents"], "replaces the contents of a file", _write_file);
+        program.new_fn("append_file", &["path", "contents"], "appends to the end of a file", _append_file);
+    }
+}
+
+fn path(args: &[Value]) -> Result<&str, Error> {
+    match args.first() {
+        Some(Value::String(path)) => Ok(path),
+        Some(value) => Err(format!("expected string for argument #1, got {}", Type::from(value.clone())).into()),
+        None => Err("expected string for argument #1, got none".into())
+    }
+}
+fn contents(args: &[Value]) -> Result<&str, Error> {
+    match args.get(1) {
+        Some(Value::String(contents)) => Ok(contents),
+        Some(value) => Err(format!("expected string for argument #2, got {}", Type::from(value.clone())).into()),
+        None => Err("expected string for argument #2, got none".into())
+    }
+}
+fn readable(path: &str, program: &Program) -> Result<PathBuf, Error> {
+    let full_path = fs::canonicalize(path).map_err(|err| format!("cannot read {path:?}: {err}"))?;
+    if !program.capabilities.read.allows(&full_path) {
+        return Err(format!("permission denied: cannot read {path:?}").into())
+    }
+    Ok(full_path)
+}
+fn writable(path: &str, program: &Program) -> Result<PathBuf, Error> {
+    let target = Path::new(path);
+    let Some(name) = target.file_name() else {
+        return Err(format!("cannot write {path:?}: not a file").into())
+    };
+    let dir = match target.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new(".")
+    };
+    let mut full_path = fs::canonicalize(dir).map_err(|err| format!("cannot write {path:?}: {err}"))?.join(name);
+    match fs::canonicalize(&full_path) {
+        Ok(resolved) => full_path = resolved,
+        // writing through a dangling symlink would create its target, wherever it points
+        Err(_) if fs::symlink_metadata(&full_path).is_ok_and(|meta| meta.file_type().is_symlink()) => {
+            return Err(format!("permission denied: cannot write {path:?} through a dangling symlink").into())
+        }
+        Err(_) => {}
+    }
+    if !program.capabilities.write.allows(&full_path) {
+        return Err(format!("permission denied: cannot write {path:?}").into())
+    }
+    Ok(full_path)
+}
+
+pub fn _print(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    print!("{}", args.iter().map(|value| value.to_string()).collect::<Vec<String>>().join(" "));
+    stdout().flush().map_err(|err| err.to_string())?;
+    Ok(None)
+}
+pub fn _println(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    println!("{}", args.iter().map(|value| value.to_string()).collect::<Vec<String>>().join(" "));
+    Ok(None)
+}
+pub fn _input(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    if let Some(prompt) = args.first() {
+        print!("{prompt}");
+        stdout().flush().map_err(|err| err.to_string())?;
+    }
+    let mut input = String::new();
+    if stdin().read_line(&mut input).map_err(|err| err.to_string())? == 0 {
+        return Ok(None)
+    }
+    let len = input.trim_end_matches(['\n', '\r']).len();
+    input.truncate(len);
+    Ok(Some(Value::String(input)))
+}
+pub fn _read_file(args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let path = path(&args)?;
+    let full_path = readable(path, program)?;
+    let contents = fs::read_to_string(full_path).map_err(|err| format!("cannot read {path:?}: {err}"))?;
+    Ok(Some(Value::String(contents)))
+}
+pub fn _write_file(args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let path = path(&args)?;
+    let contents = contents(&args)?;
+    let full_path = writable(path, program)?;
+    fs::write(full_path, contents).map_err(|err| format!("cannot write {path:?}: {err}"))?;
+    Ok(None)
+}
+pub fn _append_file(args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let path = path(&args)?;
+    let contents = contents(&args)?;
+    let full_path = writable(path, program)?;
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(full_path).map_err(|err| format!("cannot write {path:?}: {err}"))?;
+    file.write_all(contents.as_bytes()).map_err(|err| format!("cannot write {path:?}: {err}"))?;
+    Ok(None)
+}
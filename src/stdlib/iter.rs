@@ -0,0 +1,88 @@
+use crate::{
+    structure::{
+        program::Program,
+        value::*,
+        error::Error
+    },
+    implementation::evaluate::{iterate, next}
+};
+
+pub fn module(program: &mut Program) -> Object {
+    Object::default()
+        .insert("of", program.native("iter.of", &["collection"], "creates an iterator over a vector, set, map or iterator", _of))
+        .insert("next", program.native("iter.next", &["iterator"], "advances the iterator and returns the next value or none", _next))
+        .insert("collect", program.native("iter.collect", &["iterator"], "collects the remaining values into a vector", _collect))
+        .insert("map", program.native("iter.map", &["iterator", "function"], "lazily applies the function to every value", _map))
+        .insert("filter", program.native("iter.filter", &["iterator", "function"], "lazily keeps the values for which the function returns true", _filter))
+        .insert("take", program.native("iter.take", &["iterator", "count"], "yields at most count values", _take))
+        .insert("zip", program.native("iter.zip", &["left", "right"], "pairs up values of two iterators", _zip))
+}
+
+pub fn collect(iter: usize, program: &mut Program) -> Result<Vec<Value>, Error> {
+    let mut values = vec![];
+    let pos = program.pos.clone();
+    while let Some(value) = next(iter, program, &pos).map_err(Error::from)? {
+        values.push(value);
+    }
+    Ok(values)
+}
+
+pub fn _of(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    if args.is_empty() {
+        return Ok(None)
+    }
+    let iter = iterate(args.remove(0), program)?;
+    Ok(Some(Value::Iterator(iter)))
+}
+pub fn _next(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    if args.is_empty() {
+        return Ok(None)
+    }
+    let value = args.remove(0); let Value::Iterator(iter) = value else {
+        return Err(format!("expected iterator for argument #1, got {}", Type::from(value)).into())
+    };
+    let pos = program.pos.clone();
+    next(iter, program, &pos).map_err(Error::from)
+}
+pub fn _collect(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    if args.is_empty() {
+        return Ok(None)
+    }
+    let iter = iterate(args.remove(0), program)?;
+    Ok(Some(Value::Vector(collect(iter, program)?)))
+}
+pub fn _map(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    if args.len() < 2 {
+        return Ok(None)
+    }
+    let iter = iterate(args.remove(0), program)?;
+    let func = args.remove(0);
+    Ok(Some(Value::Iterator(program.iterators.create(Iter::Map { iter, func }))))
+}
+pub fn _filter(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    if args.len() < 2 {
+        return Ok(None)
+    }
+    let iter = iterate(args.remove(0), program)?;
+    let func = args.remove(0);
+    Ok(Some(Value::Iterator(program.iterators.create(Iter::Filter { iter, func }))))
+}
+pub fn _take(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    if args.len() < 2 {
+        return Ok(None)
+    }
+    let iter = iterate(args.remove(0), program)?;
+    let count = args.remove(0); let Value::Int(count) = count else {
+        return Err(format!("expected int for argument #2, got {}", Type::from(count)).into())
+    };
+    let count = usize::try_from(count).unwrap_or_default();
+    Ok(Some(Value::Iterator(program.iterators.create(Iter::Take { iter, count }))))
+}
+pub fn _zip(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    if args.len() < 2 {
+        return Ok(None)
+    }
+    let left = iterate(args.remove(0), program)?;
+    let right = iterate(args.remove(0), program)?;
+    Ok(Some(Value::Iterator(program.iterators.create(Iter::Zip(left, right)))))
+}
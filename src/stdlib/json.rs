@@ -0,0 +1,379 @@
+use std::{iter::Peekable, str::Chars};
+use crate::structure::{
+    program::Program,
+    value::*,
+    error::Error
+};
+
+pub fn module(program: &mut Program) -> Object {
+    Object::default()
+        .insert("parse", program.native("json.parse", &["string"], "decodes a json string into a value, leaving out object members that are null", _parse))
+        .insert("stringify", program.native("json.stringify", &["value", "indent?"], "encodes a value as json, optionally indented", _stringify))
+}
+
+const MAX_DEPTH: usize = 512;
+
+struct Decoder<'a> {
+    chars: Peekable<Chars<'a>>,
+    ln: usize, col: usize,
+    depth: usize
+}
+impl<'a> Decoder<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { chars: input.chars().peekable(), ln: 1, col: 1, depth: 0 }
+    }
+    fn error(&self, msg: &str) -> Error {
+        format!("invalid json at {}:{}: {msg}", self.ln, self.col).into()
+    }
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.ln += 1;
+            self.col = 1;
+        } else {
+            self.col += 1;
+        }
+        Some(c)
+    }
+    fn skip_ws(&mut self) {
+        while let Some(' ' | '\t' | '\n' | '\r') = self.peek() {
+            self.next();
+        }
+    }
+    fn expect(&mut self, expect: char) -> Result<(), Error> {
+        match self.peek() {
+            Some(c) if c == expect => {
+                self.next();
+                Ok(())
+            }
+            Some(c) => Err(self.error(&format!("expected {expect:?}, got {c:?}"))),
+            None => Err(self.error(&format!("expected {expect:?}, got end of input")))
+        }
+    }
+    fn keyword(&mut self, keyword: &str) -> Result<(), Error> {
+        for expect in keyword.chars() {
+            self.expect(expect)?;
+        }
+        Ok(())
+    }
+    fn value(&mut self, program: &mut Program) -> Result<Option<Value>, Error> {
+        self.skip_ws();
+        match self.peek() {
+            Some('{') => self.object(program).map(Some),
+            Some('[') => self.array(program).map(Some),
+            Some('"') => self.string().map(|string| Some(Value::String(string))),
+            Some('t') => self.keyword("true").map(|_| Some(Value::Boolean(true))),
+            Some('f') => self.keyword("false").map(|_| Some(Value::Boolean(false))),
+            Some('n') => self.keyword("null").map(|_| None),
+            Some('-' | '0'..='9') => self.number().map(Some),
+            Some(c) => Err(self.error(&format!("unexpected {c:?}"))),
+            None => Err(self.error("unexpected end of input"))
+        }
+    }
+    fn nested(&mut self) -> Result<(), Error> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(self.error(&format!("nesting deeper than {MAX_DEPTH} levels")))
+        }
+        Ok(())
+    }
+    fn object(&mut self, program: &mut Program) -> Result<Value, Error> {
+        self.nested()?;
+        self.expect('{')?;
+        let mut object = Object::default();
+        self.skip_ws();
+        if self.peek() == Some('}') {
+            self.next();
+        } else {
+            loop {
+                self.skip_ws();
+                let key = self.string()?;
+                self.skip_ws();
+                self.expect(':')?;
+                if let Some(value) = self.value(program)? {
+                    object.set(&key, value);
+                }
+                self.skip_ws();
+                match self.next() {
+                    Some(',') => continue,
+                    Some('}') => break,
+                    Some(c) => return Err(self.error(&format!("expected ',' or '}}', got {c:?}"))),
+                    None => return Err(self.error("expected ',' or '}', got end of input"))
+                }
+            }
+        }
+        self.depth -= 1;
+        Ok(Value::Object(program.objects.create(object)))
+    }
+    fn array(&mut self, program: &mut Program) -> Result<Value, Error> {
+        self.nested()?;
+        self.expect('[')?;
+        let mut values = vec![];
+        self.skip_ws();
+        if self.peek() == Some(']') {
+            self.next();
+        } else {
+            loop {
+                let Some(value) = self.value(program)? else {
+                    return Err(self.error("null cannot be stored in a vector"))
+                };
+                values.push(value);
+                self.skip_ws();
+                match self.next() {
+                    Some(',') => continue,
+                    Some(']') => break,
+                    Some(c) => return Err(self.error(&format!("expected ',' or ']', got {c:?}"))),
+                    None => return Err(self.error("expected ',' or ']', got end of input"))
+                }
+            }
+        }
+        self.depth -= 1;
+        Ok(Value::Vector(values))
+    }
+    fn hex(&mut self) -> Result<u32, Error> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let Some(digit) = self.next().and_then(|c| c.to_digit(16)) else {
+                return Err(self.error("expected 4 hex digits after \\u"))
+            };
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+    fn string(&mut self) -> Result<String, Error> {
+        self.expect('"')?;
+        let mut string = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(string),
+                Some('\\') => match self.next() {
+                    Some('"') => string.push('"'),
+                    Some('\\') => string.push('\\'),
+                    Some('/') => string.push('/'),
+                    Some('b') => string.push('\u{8}'),
+                    Some('f') => string.push('\u{c}'),
+                    Some('n') => string.push('\n'),
+                    Some('r') => string.push('\r'),
+                    Some('t') => string.push('\t'),
+                    Some('u') => {
+                        let mut code = self.hex()?;
+                        if (0xD800..0xDC00).contains(&code) {
+                            self.keyword("\\u")?;
+                            let low = self.hex()?;
+                            if !(0xDC00..0xE000).contains(&low) {
+                                return Err(self.error("invalid low surrogate"))
+                            }
+                            code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                        }
+                        let Some(c) = char::from_u32(code) else {
+                            return Err(self.error(&format!("invalid unicode escape {code:04x}")))
+                        };
+                        string.push(c);
+                    }
+                    Some(c) => return Err(self.error(&format!("invalid escape \\{c}"))),
+                    None => return Err(self.error("unclosed string"))
+                }
+                Some(c) if (c as u32) < 0x20 => return Err(self.error(&format!("control character {c:?} in string"))),
+                Some(c) => string.push(c),
+                None => return Err(self.error("unclosed string"))
+            }
+        }
+    }
+    fn digits(&mut self, number: &mut String) -> usize {
+        let mut count = 0;
+        while let Some(c @ '0'..='9') = self.peek() {
+            number.push(c);
+            self.next();
+            count += 1;
+        }
+        count
+    }
+    /// scans `-? (0 | [1-9][0-9]*) (. [0-9]+)? ([eE] [+-]? [0-9]+)?`, rejecting leading zeros
+    fn number(&mut self) -> Result<Value, Error> {
+        let (ln, col) = (self.ln, self.col);
+        let mut number = String::new();
+        if self.peek() == Some('-') {
+            number.push('-');
+            self.next();
+        }
+        let mut valid = if self.peek() == Some('0') {
+            number.push('0');
+            self.next();
+            !matches!(self.peek(), Some('0'..='9'))
+        } else {
+            self.digits(&mut number) > 0
+        };
+        if valid && self.peek() == Some('.') {
+            number.push('.');
+            self.next();
+            valid = self.digits(&mut number) > 0;
+        }
+        if let (true, Some(c @ ('e' | 'E'))) = (valid, self.peek()) {
+            number.push(c);
+            self.next();
+            if let Some(c @ ('+' | '-')) = self.peek() {
+                number.push(c);
+                self.next();
+            }
+            valid = self.digits(&mut number) > 0;
+        }
+        if !valid {
+            number.extend(self.peek());
+            return Err(format!("invalid json at {ln}:{col}: invalid number {number:?}").into())
+        }
+        if !number.contains(['.', 'e', 'E']) {
+            if let Ok(number) = number.parse() {
+                return Ok(Value::Int(number))
+            }
+        }
+        match number.parse() {
+            Ok(number) => Ok(Value::Float(number)),
+            Err(_) => Err(format!("invalid json at {ln}:{col}: invalid number {number:?}").into())
+        }
+    }
+}
+
+pub fn decode(input: &str, program: &mut Program) -> Result<Option<Value>, Error> {
+    let mut decoder = Decoder::new(input);
+    let value = decoder.value(program)?;
+    decoder.skip_ws();
+    if let Some(c) = decoder.peek() {
+        return Err(decoder.error(&format!("unexpected {c:?} after value")))
+    }
+    Ok(value)
+}
+
+fn quote(string: &str, json: &mut String) {
+    json.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c)
+        }
+    }
+    json.push('"');
+}
+
+struct Encoder<'a> {
+    program: &'a Program,
+    indent: String,
+    visiting: Vec<usize>,
+    json: String
+}
+impl Encoder<'_> {
+    fn newline(&mut self, depth: usize) {
+        if !self.indent.is_empty() {
+            self.json.push('\n');
+            self.json.push_str(&self.indent.repeat(depth));
+        }
+    }
+    fn entries(&mut self, entries: Vec<(String, &Value)>, depth: usize) -> Result<(), Error> {
+        self.json.push('{');
+        for (idx, (key, value)) in entries.iter().enumerate() {
+            if idx > 0 {
+                self.json.push(',');
+            }
+            self.newline(depth + 1);
+            quote(key, &mut self.json);
+            self.json.push_str(if self.indent.is_empty() { ":" } else { ": " });
+            self.value(value, depth + 1)?;
+        }
+        if !entries.is_empty() {
+            self.newline(depth);
+        }
+        self.json.push('}');
+        Ok(())
+    }
+    fn values<'v>(&mut self, values: impl Iterator<Item = &'v Value>, depth: usize) -> Result<(), Error> {
+        self.json.push('[');
+        let mut empty = true;
+        for (idx, value) in values.enumerate() {
+            if idx > 0 {
+                self.json.push(',');
+            }
+            self.newline(depth + 1);
+            self.value(value, depth + 1)?;
+            empty = false;
+        }
+        if !empty {
+            self.newline(depth);
+        }
+        self.json.push(']');
+        Ok(())
+    }
+    fn value(&mut self, value: &Value, depth: usize) -> Result<(), Error> {
+        if depth > MAX_DEPTH {
+            return Err(format!("cannot stringify values nested deeper than {MAX_DEPTH} levels").into())
+        }
+        match value {
+            Value::Int(v) => self.json.push_str(&v.to_string()),
+            Value::Float(v) if v.is_finite() => self.json.push_str(&format!("{v:?}")),
+            Value::Float(v) => return Err(format!("cannot stringify {v} as json").into()),
+            Value::Boolean(v) => self.json.push_str(&v.to_string()),
+            Value::String(v) => quote(v, &mut self.json),
+            Value::Vector(values) => self.values(values.iter(), depth)?,
+            Value::Set(values) => self.values(values.iter(), depth)?,
+            Value::Map(map) => {
+                let mut entries = vec![];
+                for (key, value) in map.iter() {
+                    let Value::String(key) = key else {
+                        return Err(format!("cannot stringify map with {} keys as json", Type::from(key.clone())).into())
+                    };
+                    entries.push((key.clone(), value));
+                }
+                entries.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
+                self.entries(entries, depth)?;
+            }
+            Value::Object(addr) => {
+                if self.visiting.contains(addr) {
+                    return Err("cannot stringify object with a reference cycle".into())
+                }
+                let program = self.program;
+                let Some(object) = program.objects.get(*addr) else {
+                    return Err("cannot stringify a dangling object".into())
+                };
+                self.visiting.push(*addr);
+                let mut entries: Vec<(String, &Value)> = object.map.iter().map(|(key, value)| (key.clone(), value)).collect();
+                entries.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
+                self.entries(entries, depth)?;
+                self.visiting.pop();
+            }
+            value => return Err(format!("cannot stringify {} as json", Type::from(value.clone())).into())
+        }
+        Ok(())
+    }
+}
+
+pub fn encode(value: &Value, indent: &str, program: &Program) -> Result<String, Error> {
+    let mut encoder = Encoder { program, indent: indent.to_string(), visiting: vec![], json: String::new() };
+    encoder.value(value, 0)?;
+    Ok(encoder.json)
+}
+
+pub fn _parse(args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    match args.first() {
+        Some(Value::String(input)) => decode(input, program),
+        Some(value) => Err(format!("expected string for argument #1, got {}", Type::from(value.clone())).into()),
+        None => Err("expected string for argument #1, got none".into())
+    }
+}
+pub fn _stringify(args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let Some(value) = args.first() else {
+        return Ok(Some(Value::String("null".into())))
+    };
+    let indent = match args.get(1) {
+        None => String::new(),
+        Some(Value::Int(n)) => " ".repeat((*n).clamp(0, 16) as usize),
+        Some(Value::String(indent)) => indent.clone(),
+        Some(value) => return Err(format!("expected int or string for argument #2, got {}", Type::from(value.clone())).into())
+    };
+    Ok(Some(Value::String(encode(value, &indent, program)?)))
+}
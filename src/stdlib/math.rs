@@ -0,0 +1,135 @@
+use std::f64::consts;
+use crate::structure::{
+    program::Program,
+    value::*,
+    error::Error
+};
+
+pub fn module(program: &mut Program) -> Object {
+    Object::default()
+        .insert("pi", Value::Float(consts::PI))
+        .insert("e", Value::Float(consts::E))
+        .insert("tau", Value::Float(consts::TAU))
+        .insert("inf", Value::Float(f64::INFINITY))
+        .insert("nan", Value::Float(f64::NAN))
+        .insert("sqrt", program.typed("math.sqrt", &["x"], "returns the square root", f64::sqrt))
+        .insert("cbrt", program.typed("math.cbrt", &["x"], "returns the cube root", f64::cbrt))
+        .insert("exp", program.typed("math.exp", &["x"], "returns e raised to the power of x", f64::exp))
+        .insert("ln", program.typed("math.ln", &["x"], "returns the natural logarithm", f64::ln))
+        .insert("log", program.typed("math.log", &["x", "base"], "returns the logarithm in the given base", f64::log))
+        .insert("sin", program.typed("math.sin", &["x"], "returns the sine of an angle in radians", f64::sin))
+        .insert("cos", program.typed("math.cos", &["x"], "returns the cosine of an angle in radians", f64::cos))
+        .insert("tan", program.typed("math.tan", &["x"], "returns the tangent of an angle in radians", f64::tan))
+        .insert("asin", program.typed("math.asin", &["x"], "returns the arcsine in radians", f64::asin))
+        .insert("acos", program.typed("math.acos", &["x"], "returns the arccosine in radians", f64::acos))
+        .insert("atan", program.typed("math.atan", &["x"], "returns the arctangent in radians", f64::atan))
+        .insert("atan2", program.typed("math.atan2", &["y", "x"], "returns the angle of the point (x, y) in radians", f64::atan2))
+        .insert("sinh", program.typed("math.sinh", &["x"], "returns the hyperbolic sine", f64::sinh))
+        .insert("cosh", program.typed("math.cosh", &["x"], "returns the hyperbolic cosine", f64::cosh))
+        .insert("tanh", program.typed("math.tanh", &["x"], "returns the hyperbolic tangent", f64::tanh))
+        .insert("asinh", program.typed("math.asinh", &["x"], "returns the inverse hyperbolic sine", f64::asinh))
+        .insert("acosh", program.typed("math.acosh", &["x"], "returns the inverse hyperbolic cosine", f64::acosh))
+        .insert("atanh", program.typed("math.atanh", &["x"], "returns the inverse hyperbolic tangent", f64::atanh))
+        .insert("floor", program.native("math.floor", &["x"], "rounds down to the nearest integer", _floor))
+        .insert("ceil", program.native("math.ceil", &["x"], "rounds up to the nearest integer", _ceil))
+        .insert("round", program.native("math.round", &["x"], "rounds to the nearest integer, halfway cases away from zero", _round))
+        .insert("trunc", program.native("math.trunc", &["x"], "rounds towards zero", _trunc))
+        .insert("min", program.native("math.min", &["values..."], "returns the smallest number", _min))
+        .insert("max", program.native("math.max", &["values..."], "returns the largest number", _max))
+        .insert("clamp", program.native("math.clamp", &["x", "min", "max"], "restricts a number to a range", _clamp))
+        .insert("gcd", program.typed("math.gcd", &["a", "b"], "returns the greatest common divisor", gcd))
+        .insert("lcm", program.typed("math.lcm", &["a", "b"], "returns the least common multiple", lcm))
+        .insert("factorial", program.typed("math.factorial", &["n"], "returns n!", factorial))
+}
+
+pub fn number(args: &[Value], idx: usize) -> Result<f64, Error> {
+    match args.get(idx) {
+        Some(Value::Int(v)) => Ok(*v as f64),
+        Some(Value::Float(v)) => Ok(*v),
+        Some(value) => Err(format!("expected number for argument #{}, got {}", idx + 1, Type::from(value.clone())).into()),
+        None => Err(format!("expected number for argument #{}, got none", idx + 1).into())
+    }
+}
+pub fn int(args: &[Value], idx: usize) -> Result<i64, Error> {
+    match args.get(idx) {
+        Some(Value::Int(v)) => Ok(*v),
+        Some(value) => Err(format!("expected int for argument #{}, got {}", idx + 1, Type::from(value.clone())).into()),
+        None => Err(format!("expected int for argument #{}, got none", idx + 1).into())
+    }
+}
+fn float(args: Vec<Value>, f: fn(f64) -> f64) -> Result<Option<Value>, Error> {
+    Ok(Some(Value::Float(f(number(&args, 0)?))))
+}
+fn rounding(args: Vec<Value>, f: fn(f64) -> f64) -> Result<Option<Value>, Error> {
+    match args.first() {
+        Some(Value::Int(v)) => Ok(Some(Value::Int(*v))),
+        _ => float(args, f)
+    }
+}
+
+pub fn _floor(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    rounding(args, f64::floor)
+}
+pub fn _ceil(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    rounding(args, f64::ceil)
+}
+pub fn _round(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    rounding(args, f64::round)
+}
+pub fn _trunc(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    rounding(args, f64::trunc)
+}
+pub fn _min(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let mut min = None;
+    for idx in 0..args.len().max(1) {
+        let value = number(&args, idx)?;
+        if min.is_none_or(|min: (f64, usize)| value < min.0) {
+            min = Some((value, idx));
+        }
+    }
+    Ok(min.map(|(_, idx)| args[idx].clone()))
+}
+pub fn _max(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let mut max = None;
+    for idx in 0..args.len().max(1) {
+        let value = number(&args, idx)?;
+        if max.is_none_or(|max: (f64, usize)| value > max.0) {
+            max = Some((value, idx));
+        }
+    }
+    Ok(max.map(|(_, idx)| args[idx].clone()))
+}
+pub fn _clamp(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let value = number(&args, 0)?;
+    let min = number(&args, 1)?;
+    let max = number(&args, 2)?;
+    if min > max {
+        return Err(format!("clamp minimum {min} is greater than maximum {max}").into())
+    }
+    if value < min {
+        Ok(Some(args[1].clone()))
+    } else if value > max {
+        Ok(Some(args[2].clone()))
+    } else {
+        Ok(Some(args[0].clone()))
+    }
+}
+fn gcd(a: i64, b: i64) -> Result<i64, String> {
+    let (mut x, mut y) = (a.unsigned_abs(), b.unsigned_abs());
+    while y != 0 {
+        (x, y) = (y, x % y);
+    }
+    i64::try_from(x).map_err(|_| format!("gcd of {a} and {b} is out of range"))
+}
+fn lcm(a: i64, b: i64) -> Result<i64, String> {
+    if a == 0 || b == 0 {
+        return Ok(0)
+    }
+    (a / gcd(a, b)?).checked_mul(b).and_then(i64::checked_abs).ok_or_else(|| format!("lcm of {a} and {b} is out of range"))
+}
+fn factorial(n: i64) -> Result<i64, String> {
+    if n < 0 {
+        return Err(format!("cannot take factorial of negative number {n}"))
+    }
+    (1..=n).try_fold(1i64, |acc, i| acc.checked_mul(i)).ok_or_else(|| format!("factorial of {n} is out of range"))
+}
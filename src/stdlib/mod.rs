@@ -0,0 +1,9 @@
+pub mod iter;
+pub mod math;
+pub mod string;
+pub mod vector;
+pub mod io;
+pub mod json;
+pub mod random;
+pub mod time;
+pub mod regex;
\ No newline at end of file
@@ -0,0 +1,134 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::{
+    structure::{
+        program::Program,
+        value::*,
+        error::Error
+    },
+    stdlib::{
+        math::{number, int},
+        vector::vector
+    }
+};
+
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: [u64; 4]
+}
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        let mut seed = seed;
+        let mut splitmix = || {
+            seed = seed.wrapping_add(0x9E3779B97F4A7C15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+            z ^ (z >> 31)
+        };
+        Self { state: [splitmix(), splitmix(), splitmix(), splitmix()] }
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        let [s0, s1, s2, s3] = &mut self.state;
+        let result = s1.wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = *s1 << 17;
+        *s2 ^= *s0;
+        *s3 ^= *s1;
+        *s1 ^= *s2;
+        *s0 ^= *s3;
+        *s2 ^= t;
+        *s3 = s3.rotate_left(45);
+        result
+    }
+    pub fn float(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+    pub fn below(&mut self, n: u64) -> u64 {
+        let zone = u64::MAX - u64::MAX % n;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % n
+            }
+        }
+    }
+}
+impl Default for Rng {
+    fn default() -> Self {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_nanos() as u64).unwrap_or_default();
+        Self::new(nanos ^ (&nanos as *const u64 as u64))
+    }
+}
+
+pub fn module(program: &mut Program) -> Object {
+    Object::default()
+        .insert("seed", program.native("random.seed", &["seed"], "reseeds the generator for reproducible sequences", _seed))
+        .insert("random", program.native("random.random", &[], "returns a float in [0, 1)", _random))
+        .insert("int", program.native("random.int", &["min", "max"], "returns an int between min and max inclusive", _int))
+        .insert("choice", program.native("random.choice", &["vector"], "returns a random element", _choice))
+        .insert("shuffle", program.native("random.shuffle", &["vector"], "returns the elements in random order", _shuffle))
+        .insert("sample", program.native("random.sample", &["vector", "count"], "returns count distinct random elements", _sample))
+        .insert("normal", program.native("random.normal", &["mean?", "deviation?"], "returns a normally distributed float", _normal))
+}
+
+pub fn _seed(args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    program.rng = Rng::new(int(&args, 0)? as u64);
+    Ok(None)
+}
+pub fn _random(_: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    Ok(Some(Value::Float(program.rng.float())))
+}
+pub fn _int(args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let min = int(&args, 0)?;
+    let max = int(&args, 1)?;
+    if min > max {
+        return Err(format!("empty range {min}..={max}").into())
+    }
+    let span = max.abs_diff(min);
+    let offset = if span == u64::MAX {
+        program.rng.next_u64()
+    } else {
+        program.rng.below(span + 1)
+    };
+    Ok(Some(Value::Int(min.wrapping_add(offset as i64))))
+}
+pub fn _choice(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let mut values = vector(&mut args, 0)?;
+    if values.is_empty() {
+        return Err("cannot choose from an empty vector".into())
+    }
+    let idx = program.rng.below(values.len() as u64) as usize;
+    Ok(Some(values.swap_remove(idx)))
+}
+pub fn _shuffle(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let mut values = vector(&mut args, 0)?;
+    for idx in (1..values.len()).rev() {
+        let other = program.rng.below(idx as u64 + 1) as usize;
+        values.swap(idx, other);
+    }
+    Ok(Some(Value::Vector(values)))
+}
+pub fn _sample(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let mut values = vector(&mut args, 0)?;
+    let k = int(&args, 1)?;
+    if k < 0 || k as usize > values.len() {
+        return Err(format!("cannot sample {k} values from a vector of length {}", values.len()).into())
+    }
+    let k = k as usize;
+    for idx in 0..k {
+        let other = idx + program.rng.below((values.len() - idx) as u64) as usize;
+        values.swap(idx, other);
+    }
+    values.truncate(k);
+    Ok(Some(Value::Vector(values)))
+}
+pub fn _normal(args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let mu = if args.is_empty() { 0. } else { number(&args, 0)? };
+    let sigma = if args.len() < 2 { 1. } else { number(&args, 1)? };
+    if sigma < 0. {
+        return Err(format!("expected non-negative standard deviation, got {sigma}").into())
+    }
+    let u1 = 1. - program.rng.float();
+    let u2 = program.rng.float();
+    let z = (-2. * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos();
+    Ok(Some(Value::Float(mu + sigma * z)))
+}
@@ -0,0 +1,666 @@
+use std::collections::HashMap;
+use crate::structure::{
+    program::Program,
+    value::*,
+    error::Error
+};
+
+const STEP_LIMIT: usize = 1_000_000;
+const PROGRAM_LIMIT: usize = 100_000;
+
+#[derive(Debug, Clone, PartialEq)]
+enum ClassItem {
+    Range(char, char),
+    Digit(bool),
+    Word(bool),
+    Space(bool)
+}
+impl ClassItem {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            ClassItem::Range(start, end) => (*start..=*end).contains(&c),
+            ClassItem::Digit(negated) => c.is_ascii_digit() != *negated,
+            ClassItem::Word(negated) => (c.is_alphanumeric() || c == '_') != *negated,
+            ClassItem::Space(negated) => c.is_whitespace() != *negated,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Empty,
+    Char(char),
+    Any,
+    Class(Vec<ClassItem>, bool),
+    Start, End,
+    Boundary(bool),
+    Group(Box<Node>, Option<usize>),
+    Concat(Vec<Node>),
+    Alt(Vec<Node>),
+    Repeat { node: Box<Node>, min: usize, max: Option<usize>, greedy: bool },
+    Backref(usize)
+}
+
+struct Compiler {
+    chars: Vec<char>,
+    idx: usize,
+    groups: usize,
+    names: Vec<(String, usize)>
+}
+impl Compiler {
+    fn error(&self, msg: &str) -> Error {
+        format!("invalid regex at position {}: {msg}", self.idx + 1).into()
+    }
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.idx).copied()
+    }
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.idx += 1;
+        Some(c)
+    }
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.idx += 1;
+            true
+        } else {
+            false
+        }
+    }
+    fn alternation(&mut self) -> Result<Node, Error> {
+        let mut branches = vec![self.concat()?];
+        while self.eat('|') {
+            branches.push(self.concat()?);
+        }
+        if branches.len() == 1 {
+            return Ok(branches.remove(0))
+        }
+        Ok(Node::Alt(branches))
+    }
+    fn concat(&mut self) -> Result<Node, Error> {
+        let mut nodes = vec![];
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break
+            }
+            let atom = self.atom()?;
+            nodes.push(self.quantifier(atom)?);
+        }
+        match nodes.len() {
+            0 => Ok(Node::Empty),
+            1 => Ok(nodes.remove(0)),
+            _ => Ok(Node::Concat(nodes))
+        }
+    }
+    fn number(&mut self) -> Option<usize> {
+        let start = self.idx;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.idx += 1;
+        }
+        self.chars[start..self.idx].iter().collect::<String>().parse().ok()
+    }
+    fn quantifier(&mut self, atom: Node) -> Result<Node, Error> {
+        let start = self.idx;
+        let (min, max) = match self.peek() {
+            Some('*') => { self.idx += 1; (0, None) }
+            Some('+') => { self.idx += 1; (1, None) }
+            Some('?') => { self.idx += 1; (0, Some(1)) }
+            Some('{') => {
+                self.idx += 1;
+                let Some(min) = self.number() else {
+                    self.idx = start;
+                    return Ok(atom)
+                };
+                let max = if self.eat(',') {
+                    self.number()
+                } else {
+                    Some(min)
+                };
+                if !self.eat('}') {
+                    self.idx = start;
+                    return Ok(atom)
+                }
+                if max.is_some_and(|max| max < min) {
+                    return Err(self.error(&format!("repetition range {{{min},{}}} is reversed", max.unwrap_or_default())))
+                }
+                (min, max)
+            }
+            _ => return Ok(atom)
+        };
+        if matches!(atom, Node::Start | Node::End | Node::Boundary(_) | Node::Empty) {
+            return Err(self.error("nothing to repeat"))
+        }
+        let greedy = !self.eat('?');
+        if matches!(self.peek(), Some('*' | '+' | '?')) {
+            return Err(self.error("nested quantifier"))
+        }
+        Ok(Node::Repeat { node: Box::new(atom), min, max, greedy })
+    }
+    fn escape(&mut self, in_class: bool) -> Result<Result<ClassItem, char>, Error> {
+        let Some(c) = self.next() else {
+            return Err(self.error("trailing backslash"))
+        };
+        Ok(match c {
+            'd' => Ok(ClassItem::Digit(false)),
+            'D' => Ok(ClassItem::Digit(true)),
+            'w' => Ok(ClassItem::Word(false)),
+            'W' => Ok(ClassItem::Word(true)),
+            's' => Ok(ClassItem::Space(false)),
+            'S' => Ok(ClassItem::Space(true)),
+            'n' => Err('\n'),
+            'r' => Err('\r'),
+            't' => Err('\t'),
+            '0' => Err('\0'),
+            c if c.is_alphanumeric() && !in_class => return Err(self.error(&format!("unknown escape \\{c}"))),
+            c => Err(c)
+        })
+    }
+    fn class(&mut self) -> Result<Node, Error> {
+        let negated = self.eat('^');
+        let mut items = vec![];
+        let mut first = true;
+        loop {
+            let Some(c) = self.next() else {
+                return Err(self.error("unclosed character class"))
+            };
+            let start = match c {
+                ']' if !first => break,
+                '\\' => match self.escape(true)? {
+                    Ok(item) => {
+                        items.push(item);
+                        first = false;
+                        continue
+                    }
+                    Err(c) => c
+                }
+                c => c
+            };
+            first = false;
+            if self.peek() == Some('-') && self.chars.get(self.idx + 1).is_some_and(|c| *c != ']') {
+                self.idx += 1;
+                let end = match self.next() {
+                    Some('\\') => match self.escape(true)? {
+                        Ok(_) => return Err(self.error("invalid range in character class")),
+                        Err(c) => c
+                    }
+                    Some(c) => c,
+                    None => return Err(self.error("unclosed character class"))
+                };
+                if end < start {
+                    return Err(self.error(&format!("invalid range {start}-{end} in character class")))
+                }
+                items.push(ClassItem::Range(start, end));
+            } else {
+                items.push(ClassItem::Range(start, start));
+            }
+        }
+        Ok(Node::Class(items, negated))
+    }
+    fn group(&mut self) -> Result<Node, Error> {
+        let mut capture = true;
+        let mut name = None;
+        if self.eat('?') {
+            if self.eat(':') {
+                capture = false;
+            } else if self.eat('<') || (self.eat('P') && self.eat('<')) {
+                let start = self.idx;
+                while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                    self.idx += 1;
+                }
+                let ident: String = self.chars[start..self.idx].iter().collect();
+                if ident.is_empty() || !self.eat('>') {
+                    return Err(self.error("invalid group name"))
+                }
+                name = Some(ident);
+            } else {
+                return Err(self.error("unsupported group syntax"))
+            }
+        }
+        let idx = if capture {
+            self.groups += 1;
+            if let Some(name) = name {
+                self.names.push((name, self.groups));
+            }
+            Some(self.groups)
+        } else {
+            None
+        };
+        let inner = self.alternation()?;
+        if !self.eat(')') {
+            return Err(self.error("unclosed group"))
+        }
+        Ok(Node::Group(Box::new(inner), idx))
+    }
+    fn atom(&mut self) -> Result<Node, Error> {
+        let Some(c) = self.next() else {
+            return Ok(Node::Empty)
+        };
+        match c {
+            '(' => self.group(),
+            '[' => self.class(),
+            '.' => Ok(Node::Any),
+            '^' => Ok(Node::Start),
+            '$' => Ok(Node::End),
+            '*' | '+' | '?' => {
+                self.idx -= 1;
+                Err(self.error("nothing to repeat"))
+            }
+            '\\' => match self.peek() {
+                Some('b') => { self.idx += 1; Ok(Node::Boundary(true)) }
+                Some('B') => { self.idx += 1; Ok(Node::Boundary(false)) }
+                Some('1'..='9') => {
+                    let Some(group) = self.number() else {
+                        return Err(self.error("invalid backreference"))
+                    };
+                    if group > self.groups {
+                        return Err(self.error(&format!("backreference to undefined group {group}")))
+                    }
+                    Ok(Node::Backref(group))
+                }
+                _ => match self.escape(false)? {
+                    Ok(item) => Ok(Node::Class(vec![item], false)),
+                    Err(c) => Ok(Node::Char(c))
+                }
+            }
+            c => Ok(Node::Char(c))
+        }
+    }
+}
+
+type Captures = Vec<Option<(usize, usize)>>;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Inst {
+    Char(char),
+    Any,
+    Class(Vec<ClassItem>, bool),
+    Start, End,
+    Boundary(bool),
+    Backref(usize),
+    Split(usize, usize),
+    Jmp(usize),
+    /// stores the current position in a slot
+    Mark(usize),
+    /// fails if nothing was consumed since the slot was marked
+    Progress(usize),
+    /// captures the group from the marked slot to the current position
+    Close { group: usize, mark: usize },
+    Match
+}
+
+struct Emitter {
+    insts: Vec<Inst>,
+    slots: usize
+}
+impl Emitter {
+    fn slot(&mut self) -> usize {
+        self.slots += 1;
+        self.slots - 1
+    }
+    fn split(at: usize, skip: usize, greedy: bool) -> Inst {
+        if greedy { Inst::Split(at, skip) } else { Inst::Split(skip, at) }
+    }
+    fn emit(&mut self, node: &Node) -> Result<(), Error> {
+        if self.insts.len() > PROGRAM_LIMIT {
+            return Err("invalid regex: pattern is too large".into())
+        }
+        match node {
+            Node::Empty => {}
+            Node::Char(c) => self.insts.push(Inst::Char(*c)),
+            Node::Any => self.insts.push(Inst::Any),
+            Node::Class(items, negated) => self.insts.push(Inst::Class(items.clone(), *negated)),
+            Node::Start => self.insts.push(Inst::Start),
+            Node::End => self.insts.push(Inst::End),
+            Node::Boundary(expect) => self.insts.push(Inst::Boundary(*expect)),
+            Node::Backref(idx) => self.insts.push(Inst::Backref(*idx)),
+            Node::Group(inner, None) => self.emit(inner)?,
+            Node::Group(inner, Some(group)) => {
+                let mark = self.slot();
+                self.insts.push(Inst::Mark(mark));
+                self.emit(inner)?;
+                self.insts.push(Inst::Close { group: *group, mark });
+            }
+            Node::Concat(nodes) => for node in nodes {
+                self.emit(node)?;
+            }
+            Node::Alt(branches) => {
+                let mut jumps = vec![];
+                for (idx, branch) in branches.iter().enumerate() {
+                    if idx + 1 == branches.len() {
+                        self.emit(branch)?;
+                        break
+                    }
+                    let split = self.insts.len();
+                    self.insts.push(Inst::Split(split + 1, split + 1));
+                    self.emit(branch)?;
+                    jumps.push(self.insts.len());
+                    self.insts.push(Inst::Jmp(0));
+                    self.insts[split] = Inst::Split(split + 1, self.insts.len());
+                }
+                let end = self.insts.len();
+                for jump in jumps {
+                    self.insts[jump] = Inst::Jmp(end);
+                }
+            }
+            Node::Repeat { node, min, max, greedy } => {
+                for _ in 0..*min {
+                    self.emit(node)?;
+                }
+                let mut splits = vec![];
+                let optional = match max {
+                    Some(max) => max - min,
+                    None => 1
+                };
+                for _ in 0..optional {
+                    splits.push(self.insts.len());
+                    self.insts.push(Inst::Jmp(0));
+                    let mark = self.slot();
+                    self.insts.push(Inst::Mark(mark));
+                    self.emit(node)?;
+                    self.insts.push(Inst::Progress(mark));
+                }
+                if max.is_none() {
+                    self.insts.push(Inst::Jmp(splits[0]));
+                }
+                let end = self.insts.len();
+                for split in splits {
+                    self.insts[split] = Self::split(split + 1, end, *greedy);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+enum Job {
+    Thread(usize, usize),
+    Restore(usize, Option<usize>)
+}
+
+/// a backtracking matcher that keeps its alternatives on a heap stack instead of recursing
+struct Matcher<'a> {
+    input: &'a [char],
+    insts: &'a [Inst],
+    steps: usize
+}
+impl Matcher<'_> {
+    fn is_word(&self, pos: usize) -> bool {
+        self.input.get(pos).is_some_and(|c| c.is_alphanumeric() || *c == '_')
+    }
+    fn set(slots: &mut [Option<usize>], jobs: &mut Vec<Job>, slot: usize, value: Option<usize>) {
+        jobs.push(Job::Restore(slot, slots[slot]));
+        slots[slot] = value;
+    }
+    fn run(&mut self, start: usize, slots: &mut [Option<usize>]) -> bool {
+        let mut jobs = vec![Job::Thread(0, start)];
+        while let Some(job) = jobs.pop() {
+            let (mut pc, mut pos) = match job {
+                Job::Thread(pc, pos) => (pc, pos),
+                Job::Restore(slot, value) => {
+                    slots[slot] = value;
+                    continue
+                }
+            };
+            loop {
+                self.steps += 1;
+                if self.steps > STEP_LIMIT {
+                    return false
+                }
+                let matched = match &self.insts[pc] {
+                    Inst::Char(c) => self.input.get(pos) == Some(c),
+                    Inst::Any => self.input.get(pos).is_some_and(|c| *c != '\n'),
+                    Inst::Class(items, negated) => self.input.get(pos)
+                        .is_some_and(|c| items.iter().any(|item| item.matches(*c)) != *negated),
+                    Inst::Start => pos == 0,
+                    Inst::End => pos == self.input.len(),
+                    Inst::Boundary(expect) => {
+                        let boundary = pos > 0 && self.is_word(pos - 1);
+                        (boundary != self.is_word(pos)) == *expect
+                    }
+                    Inst::Backref(group) => match (slots[group * 2], slots[group * 2 + 1]) {
+                        (Some(start), Some(end)) => {
+                            let len = end - start;
+                            if pos + len > self.input.len() || self.input[start..end] != self.input[pos..pos + len] {
+                                break
+                            }
+                            pos += len;
+                            true
+                        }
+                        _ => true
+                    }
+                    Inst::Split(at, skip) => {
+                        jobs.push(Job::Thread(*skip, pos));
+                        pc = *at;
+                        continue
+                    }
+                    Inst::Jmp(at) => {
+                        pc = *at;
+                        continue
+                    }
+                    Inst::Mark(slot) => {
+                        Self::set(slots, &mut jobs, *slot, Some(pos));
+                        true
+                    }
+                    Inst::Progress(slot) => slots[*slot] != Some(pos),
+                    Inst::Close { group, mark } => {
+                        let start = slots[*mark];
+                        Self::set(slots, &mut jobs, group * 2, start);
+                        Self::set(slots, &mut jobs, group * 2 + 1, Some(pos));
+                        true
+                    }
+                    Inst::Match => {
+                        slots[0] = Some(start);
+                        slots[1] = Some(pos);
+                        return true
+                    }
+                };
+                if !matched {
+                    break
+                }
+                if matches!(self.insts[pc], Inst::Char(_) | Inst::Any | Inst::Class(..)) {
+                    pos += 1;
+                }
+                pc += 1;
+            }
+        }
+        false
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regex {
+    pub pattern: String,
+    insts: Vec<Inst>,
+    slots: usize,
+    groups: usize,
+    names: Vec<(String, usize)>
+}
+impl Regex {
+    pub fn new(pattern: &str) -> Result<Self, Error> {
+        let mut compiler = Compiler { chars: pattern.chars().collect(), idx: 0, groups: 0, names: vec![] };
+        let node = compiler.alternation()?;
+        if compiler.idx < compiler.chars.len() {
+            return Err(compiler.error("unmatched ')'"))
+        }
+        let mut emitter = Emitter { insts: vec![], slots: (compiler.groups + 1) * 2 };
+        emitter.emit(&node)?;
+        emitter.insts.push(Inst::Match);
+        Ok(Self { pattern: pattern.to_string(), insts: emitter.insts, slots: emitter.slots, groups: compiler.groups, names: compiler.names })
+    }
+    fn search(&self, input: &[char], start: usize) -> Result<Option<Captures>, Error> {
+        let mut matcher = Matcher { input, insts: &self.insts, steps: 0 };
+        for pos in start..=input.len() {
+            let mut slots = vec![None; self.slots];
+            if matcher.run(pos, &mut slots) {
+                let caps = (0..=self.groups).map(|group| match (slots[group * 2], slots[group * 2 + 1]) {
+                    (Some(start), Some(end)) => Some((start, end)),
+                    _ => None
+                }).collect();
+                return Ok(Some(caps))
+            }
+            if matcher.steps > STEP_LIMIT {
+                return Err(format!("regex {:?} is too expensive to match", self.pattern).into())
+            }
+        }
+        Ok(None)
+    }
+    fn search_all(&self, input: &[char]) -> Result<Vec<Captures>, Error> {
+        let mut matches = vec![];
+        let mut pos = 0;
+        while pos <= input.len() {
+            let Some(caps) = self.search(input, pos)? else {
+                break
+            };
+            let Some((start, end)) = caps[0] else {
+                break
+            };
+            pos = if end == start { end + 1 } else { end };
+            matches.push(caps);
+        }
+        Ok(matches)
+    }
+    fn expand(&self, replacement: &str, input: &[char], caps: &Captures) -> Result<String, Error> {
+        let group = |idx: usize| caps.get(idx).copied().flatten().map(|(start, end)| input[start..end].iter().collect::<String>());
+        let mut string = String::new();
+        let mut chars = replacement.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '$' {
+                string.push(c);
+                continue
+            }
+            match chars.peek() {
+                Some('$') => {
+                    chars.next();
+                    string.push('$');
+                }
+                Some('0'..='9') => {
+                    let mut digits = String::new();
+                    let mut idx = Some(0usize);
+                    while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                        digits.push(chars.next().unwrap_or_default());
+                        idx = idx.and_then(|idx| idx.checked_mul(10)?.checked_add(digit as usize));
+                    }
+                    let Some(idx) = idx.filter(|idx| *idx <= self.groups) else {
+                        return Err(format!("replacement refers to undefined group {digits}").into())
+                    };
+                    string.push_str(&group(idx).unwrap_or_default());
+                }
+                Some('{') => {
+                    chars.next();
+                    let name: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                    let idx = match name.parse::<usize>() {
+                        Ok(idx) if idx <= self.groups => idx,
+                        Ok(idx) => return Err(format!("replacement refers to undefined group {idx}").into()),
+                        Err(_) => match self.names.iter().find(|(other, _)| *other == name) {
+                            Some((_, idx)) => *idx,
+                            None => return Err(format!("replacement refers to undefined group {name:?}").into())
+                        }
+                    };
+                    string.push_str(&group(idx).unwrap_or_default());
+                }
+                _ => string.push('$')
+            }
+        }
+        Ok(string)
+    }
+}
+
+pub fn module(program: &mut Program) -> Object {
+    Object::default()
+        .insert("new", program.native("re.new", &["pattern"], "compiles a pattern into a regex", _regex))
+        .insert("matches", program.native("re.matches", &["regex", "string"], "checks whether the regex matches anywhere in the string", _matches))
+        .insert("find", program.native("re.find", &["regex", "string"], "returns the first match or none", _find))
+        .insert("find_all", program.native("re.find_all", &["regex", "string"], "returns all non-overlapping matches", _find_all))
+        .insert("captures", program.native("re.captures", &["regex", "string"], "returns the match, its position, groups and named groups of the first match", _captures))
+        .insert("replace", program.native("re.replace", &["regex", "string", "replacement"], "replaces every match, expanding $1, ${name} and $$ in the replacement", _replace))
+}
+
+fn regex(args: &[Value], program: &Program) -> Result<Regex, Error> {
+    match args.first() {
+        Some(Value::Regex(addr)) => Ok(program.regexes.get(*addr).unwrap().clone()),
+        Some(Value::String(pattern)) => Regex::new(pattern),
+        Some(value) => Err(format!("expected regex for argument #1, got {}", Type::from(value.clone())).into()),
+        None => Err("expected regex for argument #1, got none".into())
+    }
+}
+fn input(args: &[Value], idx: usize) -> Result<Vec<char>, Error> {
+    match args.get(idx) {
+        Some(Value::String(string)) => Ok(string.chars().collect()),
+        Some(value) => Err(format!("expected string for argument #{}, got {}", idx + 1, Type::from(value.clone())).into()),
+        None => Err(format!("expected string for argument #{}, got none", idx + 1).into())
+    }
+}
+fn slice(input: &[char], span: (usize, usize)) -> Value {
+    Value::String(input[span.0..span.1].iter().collect())
+}
+
+pub fn _regex(args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let regex = match args.first() {
+        Some(Value::Regex(_)) => return Ok(args.first().cloned()),
+        Some(Value::String(pattern)) => Regex::new(pattern)?,
+        Some(value) => return Err(format!("expected string for argument #1, got {}", Type::from(value.clone())).into()),
+        None => return Err("expected string for argument #1, got none".into())
+    };
+    Ok(Some(Value::Regex(program.regexes.create(regex))))
+}
+pub fn _matches(args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let regex = regex(&args, program)?;
+    let input = input(&args, 1)?;
+    Ok(Some(Value::Boolean(regex.search(&input, 0)?.is_some())))
+}
+pub fn _find(args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let regex = regex(&args, program)?;
+    let input = input(&args, 1)?;
+    Ok(regex.search(&input, 0)?.and_then(|caps| caps[0]).map(|span| slice(&input, span)))
+}
+pub fn _find_all(args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let regex = regex(&args, program)?;
+    let input = input(&args, 1)?;
+    let matches = regex.search_all(&input)?;
+    Ok(Some(Value::Vector(matches.into_iter().filter_map(|caps| caps[0]).map(|span| slice(&input, span)).collect())))
+}
+pub fn _captures(args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let regex = regex(&args, program)?;
+    let input = input(&args, 1)?;
+    let Some(caps) = regex.search(&input, 0)? else {
+        return Ok(None)
+    };
+    let Some((start, end)) = caps[0] else {
+        return Ok(None)
+    };
+    let groups = caps.iter().map(|span| span.map(|span| slice(&input, span)).unwrap_or(Value::String(String::new()))).collect();
+    let mut named = HashMap::new();
+    for (name, idx) in regex.names.iter() {
+        if let Some(span) = caps[*idx] {
+            named.insert(Value::String(name.clone()), slice(&input, span));
+        }
+    }
+    let captures = Object::default()
+        .insert("match", slice(&input, (start, end)))
+        .insert("start", Value::Int(start as i64))
+        .insert("end", Value::Int(end as i64))
+        .insert("groups", Value::Vector(groups))
+        .insert("named", Value::Map(named));
+    Ok(Some(Value::Object(program.objects.create(captures))))
+}
+pub fn _replace(args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let regex = regex(&args, program)?;
+    let input = input(&args, 1)?;
+    let replacement = match args.get(2) {
+        Some(Value::String(replacement)) => replacement,
+        Some(value) => return Err(format!("expected string for argument #3, got {}", Type::from(value.clone())).into()),
+        None => return Err("expected string for argument #3, got none".into())
+    };
+    let mut string = String::new();
+    let mut last = 0;
+    for caps in regex.search_all(&input)? {
+        let Some((start, end)) = caps[0] else {
+            continue
+        };
+        string.extend(&input[last..start]);
+        string.push_str(&regex.expand(replacement, &input, &caps)?);
+        last = end;
+    }
+    string.extend(&input[last..]);
+    Ok(Some(Value::String(string)))
+}
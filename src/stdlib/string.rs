@@ -0,0 +1,157 @@
+use crate::structure::{
+    program::Program,
+    value::*,
+    error::Error
+};
+
+pub fn module(program: &mut Program) -> Object {
+    Object::default()
+        .insert("len", program.native("string.len", &["string"], "returns the number of characters", _len))
+        .insert("upper", program.native("string.upper", &["string"], "converts to uppercase", _upper))
+        .insert("lower", program.native("string.lower", &["string"], "converts to lowercase", _lower))
+        .insert("trim", program.native("string.trim", &["string"], "removes leading and trailing whitespace", _trim))
+        .insert("split", program.native("string.split", &["string", "separator?"], "splits on a separator, or on whitespace if none is given", _split))
+        .insert("join", program.native("string.join", &["vector", "separator?"], "joins strings with a separator", _join))
+        .insert("replace", program.native("string.replace", &["string", "from", "to"], "replaces every occurrence of a substring", _replace))
+        .insert("find", program.native("string.find", &["string", "substring"], "returns the character index of a substring or none", _find))
+        .insert("starts_with", program.native("string.starts_with", &["string", "prefix"], "checks whether the string starts with the prefix", _starts_with))
+        .insert("ends_with", program.native("string.ends_with", &["string", "suffix"], "checks whether the string ends with the suffix", _ends_with))
+        .insert("repeat", program.native("string.repeat", &["string", "count"], "repeats the string count times", _repeat))
+        .insert("pad_left", program.native("string.pad_left", &["string", "width", "fill?"], "pads the start of the string to the given width", _pad_left))
+        .insert("pad_right", program.native("string.pad_right", &["string", "width", "fill?"], "pads the end of the string to the given width", _pad_right))
+        .insert("chars", program.native("string.chars", &["string"], "splits the string into characters", _chars))
+        .insert("reverse", program.native("string.reverse", &["string"], "reverses the string, keeping combining characters attached", _reverse))
+}
+
+pub fn string(args: &[Value], idx: usize) -> Result<&str, Error> {
+    match args.get(idx) {
+        Some(Value::String(v)) => Ok(v),
+        Some(value) => Err(format!("expected string for argument #{}, got {}", idx + 1, Type::from(value.clone())).into()),
+        None => Err(format!("expected string for argument #{}, got none", idx + 1).into())
+    }
+}
+pub fn count(args: &[Value], idx: usize) -> Result<usize, Error> {
+    match args.get(idx) {
+        Some(Value::Int(v)) => usize::try_from(*v).map_err(|_| format!("expected non-negative int for argument #{}, got {v}", idx + 1).into()),
+        Some(value) => Err(format!("expected int for argument #{}, got {}", idx + 1, Type::from(value.clone())).into()),
+        None => Err(format!("expected int for argument #{}, got none", idx + 1).into())
+    }
+}
+
+fn extends(c: char) -> bool {
+    matches!(c as u32,
+        0x0300..=0x036F | 0x1AB0..=0x1AFF | 0x1DC0..=0x1DFF | 0x20D0..=0x20FF | 0xFE20..=0xFE2F
+        | 0xFE00..=0xFE0F | 0x1F3FB..=0x1F3FF | 0x200D | 0xE0020..=0xE007F
+    )
+}
+pub fn clusters(string: &str) -> Vec<&str> {
+    let mut clusters = vec![];
+    let mut start = 0;
+    let mut joined = false;
+    for (idx, c) in string.char_indices() {
+        if idx > start && !extends(c) && !joined {
+            clusters.push(&string[start..idx]);
+            start = idx;
+        }
+        joined = c == '\u{200D}';
+    }
+    if start < string.len() {
+        clusters.push(&string[start..]);
+    }
+    clusters
+}
+
+pub fn _len(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    Ok(Some(Value::Int(string(&args, 0)?.chars().count() as i64)))
+}
+pub fn _upper(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    Ok(Some(Value::String(string(&args, 0)?.to_uppercase())))
+}
+pub fn _lower(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    Ok(Some(Value::String(string(&args, 0)?.to_lowercase())))
+}
+pub fn _trim(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    Ok(Some(Value::String(string(&args, 0)?.trim().to_string())))
+}
+pub fn _split(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let s = string(&args, 0)?;
+    let parts: Vec<Value> = if args.len() < 2 {
+        s.split_whitespace().map(|part| Value::String(part.to_string())).collect()
+    } else {
+        let sep = string(&args, 1)?;
+        if sep.is_empty() {
+            return Err("cannot split by an empty string".into())
+        }
+        s.split(sep).map(|part| Value::String(part.to_string())).collect()
+    };
+    Ok(Some(Value::Vector(parts)))
+}
+pub fn _join(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let values = match args.first() {
+        Some(Value::Vector(values)) => values,
+        Some(value) => return Err(format!("expected vector for argument #1, got {}", Type::from(value.clone())).into()),
+        None => return Err("expected vector for argument #1, got none".into())
+    };
+    let sep = if args.len() < 2 { "" } else { string(&args, 1)? };
+    Ok(Some(Value::String(values.iter().map(|value| value.to_string()).collect::<Vec<String>>().join(sep))))
+}
+pub fn _replace(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let s = string(&args, 0)?;
+    let from = string(&args, 1)?;
+    let to = string(&args, 2)?;
+    if from.is_empty() {
+        return Err("cannot replace an empty string".into())
+    }
+    Ok(Some(Value::String(s.replace(from, to))))
+}
+pub fn _find(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let s = string(&args, 0)?;
+    let sub = string(&args, 1)?;
+    Ok(s.find(sub).map(|idx| Value::Int(s[..idx].chars().count() as i64)))
+}
+pub fn _starts_with(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let s = string(&args, 0)?;
+    let prefix = string(&args, 1)?;
+    Ok(Some(Value::Boolean(s.starts_with(prefix))))
+}
+pub fn _ends_with(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let s = string(&args, 0)?;
+    let suffix = string(&args, 1)?;
+    Ok(Some(Value::Boolean(s.ends_with(suffix))))
+}
+pub fn _repeat(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let s = string(&args, 0)?;
+    let n = count(&args, 1)?;
+    if s.len().checked_mul(n).is_none_or(|len| len > u32::MAX as usize) {
+        return Err(format!("cannot repeat string {n} times").into())
+    }
+    Ok(Some(Value::String(s.repeat(n))))
+}
+fn padding(args: &[Value]) -> Result<(&str, String), Error> {
+    let s = string(args, 0)?;
+    let width = count(args, 1)?;
+    let fill = if args.len() < 3 { " " } else { string(args, 2)? };
+    let mut chars = fill.chars();
+    let (Some(fill), None) = (chars.next(), chars.next()) else {
+        return Err(format!("expected a single character for argument #3, got {fill:?}").into())
+    };
+    let n = width.saturating_sub(s.chars().count());
+    if fill.len_utf8().checked_mul(n).and_then(|len| len.checked_add(s.len())).is_none_or(|len| len > u32::MAX as usize) {
+        return Err(format!("cannot pad string to width {width}").into())
+    }
+    Ok((s, std::iter::repeat_n(fill, n).collect()))
+}
+pub fn _pad_left(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let (s, padding) = padding(&args)?;
+    Ok(Some(Value::String(padding + s)))
+}
+pub fn _pad_right(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let (s, padding) = padding(&args)?;
+    Ok(Some(Value::String(s.to_string() + &padding)))
+}
+pub fn _chars(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    Ok(Some(Value::Vector(string(&args, 0)?.chars().map(|c| Value::String(c.to_string())).collect())))
+}
+pub fn _reverse(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    Ok(Some(Value::String(clusters(string(&args, 0)?).into_iter().rev().collect())))
+}
@@ -0,0 +1,430 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::{
+    structure::{
+        program::Program,
+        value::*,
+        error::Error,
+        tokens::Token,
+        time::*
+    },
+    implementation::lex::lex
+};
+
+const MONTHS: [&str; 12] = ["January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December"];
+const WEEKDAYS: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+const ISO_PATTERNS: [&str; 14] = [
+    "%Y-%m-%dT%H:%M:%S.%f%z", "%Y-%m-%dT%H:%M:%S.%f",
+    "%Y-%m-%dT%H:%M:%S%z", "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M%z", "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M:%S.%f%z", "%Y-%m-%d %H:%M:%S.%f",
+    "%Y-%m-%d %H:%M:%S%z", "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M%z", "%Y-%m-%d %H:%M",
+    "%Y-%m-%d%z", "%Y-%m-%d"
+];
+
+pub fn module(program: &mut Program) -> Object {
+    Object::default()
+        .insert("now", program.native("time.now", &[], "returns the current date in UTC", _now))
+        .insert("date", program.native("time.date", &["year", "month?", "day?", "hour?", "minute?", "second?", "millisecond?", "offset?"], "creates a date from calendar fields", _date))
+        .insert("parse", program.native("time.parse", &["string", "pattern?"], "parses a date using a strftime pattern, or ISO 8601 if none is given", _parse))
+        .insert("format", program.native("time.format", &["date", "pattern?"], "formats a date using a strftime pattern, or ISO 8601 if none is given", _format))
+        .insert("utc", program.native("time.utc", &["date"], "returns the same instant with a zero offset", _utc))
+        .insert("offset", program.native("time.offset", &["date", "offset?"], "returns the offset of a date, or the same instant with a new offset", _offset))
+        .insert("parts", program.native("time.parts", &["date"], "returns the calendar fields of a date as an object", _parts))
+        .insert("timestamp", program.native("time.timestamp", &["date"], "returns the seconds since the unix epoch", _timestamp))
+        .insert("from_timestamp", program.native("time.from_timestamp", &["seconds", "offset?"], "creates a date from seconds since the unix epoch", _from_timestamp))
+        .insert("seconds", program.native("time.seconds", &["duration"], "returns the length of a duration in seconds", _seconds))
+        .insert("duration", program.native("time.duration", &["value"], "creates a duration from seconds or a literal such as \"1h30m\"", _duration))
+}
+
+fn date(args: &[Value], idx: usize) -> Result<Date, Error> {
+    match args.get(idx) {
+        Some(Value::Date(date)) => Ok(*date),
+        Some(value) => Err(format!("expected date for argument #{}, got {}", idx + 1, Type::from(value.clone())).into()),
+        None => Err(format!("expected date for argument #{}, got none", idx + 1).into())
+    }
+}
+fn string(args: &[Value], idx: usize) -> Result<&str, Error> {
+    match args.get(idx) {
+        Some(Value::String(string)) => Ok(string),
+        Some(value) => Err(format!("expected string for argument #{}, got {}", idx + 1, Type::from(value.clone())).into()),
+        None => Err(format!("expected string for argument #{}, got none", idx + 1).into())
+    }
+}
+fn int(args: &[Value], idx: usize, default: i64) -> Result<i64, Error> {
+    match args.get(idx) {
+        Some(Value::Int(v)) => Ok(*v),
+        Some(value) => Err(format!("expected int for argument #{}, got {}", idx + 1, Type::from(value.clone())).into()),
+        None => Ok(default)
+    }
+}
+fn offset(args: &[Value], idx: usize) -> Result<i32, Error> {
+    match args.get(idx) {
+        None => Ok(0),
+        Some(Value::Duration(v)) if v.abs() < DAY && v % SECOND == 0 => Ok((v / SECOND) as i32),
+        Some(Value::Duration(v)) => Err(format!("invalid utc offset {}", format_duration(*v)).into()),
+        Some(Value::String(string)) => {
+            let mut parser = Parser { input: string, idx: 0 };
+            let offset = parser.offset()?;
+            if parser.idx < string.len() {
+                return Err(format!("invalid utc offset {string:?}").into())
+            }
+            Ok(offset)
+        }
+        Some(value) => Err(format!("expected duration or string for argument #{}, got {}", idx + 1, Type::from(value.clone())).into())
+    }
+}
+
+pub fn format(date: &Date, pattern: &str) -> Result<String, Error> {
+    let civil = date.civil();
+    let hour12 = match civil.hour % 12 { 0 => 12, hour => hour };
+    let mut string = String::new();
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            string.push(c);
+            continue
+        }
+        let Some(spec) = chars.next() else {
+            return Err("incomplete format specifier at end of pattern".into())
+        };
+        let part = match spec {
+            'Y' => format!("{:04}", civil.year),
+            'y' => format!("{:02}", civil.year.rem_euclid(100)),
+            'm' => format!("{:02}", civil.month),
+            'd' => format!("{:02}", civil.day),
+            'e' => format!("{:2}", civil.day),
+            'H' => format!("{:02}", civil.hour),
+            'I' => format!("{:02}", hour12),
+            'M' => format!("{:02}", civil.minute),
+            'S' => format!("{:02}", civil.second),
+            'f' => format!("{:03}", civil.millisecond),
+            'p' => if civil.hour < 12 { "AM" } else { "PM" }.to_string(),
+            'j' => format!("{:03}", civil.yearday),
+            'a' => WEEKDAYS[civil.weekday as usize][..3].to_string(),
+            'A' => WEEKDAYS[civil.weekday as usize].to_string(),
+            'b' => MONTHS[civil.month as usize - 1][..3].to_string(),
+            'B' => MONTHS[civil.month as usize - 1].to_string(),
+            'u' => (civil.weekday + 1).to_string(),
+            'w' => ((civil.weekday + 1) % 7).to_string(),
+            'z' => offset_string(date.offset, false),
+            'Z' => if date.offset == 0 { "UTC".to_string() } else { offset_string(date.offset, true) },
+            's' => date.timestamp.div_euclid(SECOND).to_string(),
+            'F' => format!("{:04}-{:02}-{:02}", civil.year, civil.month, civil.day),
+            'T' => format!("{:02}:{:02}:{:02}", civil.hour, civil.minute, civil.second),
+            '%' => "%".to_string(),
+            spec => return Err(format!("unknown format specifier %{spec}").into())
+        };
+        string.push_str(&part);
+    }
+    Ok(string)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    idx: usize
+}
+impl Parser<'_> {
+    fn error(&self, expected: &str) -> Error {
+        let col = self.input[..self.idx].chars().count() + 1;
+        match self.input[self.idx..].chars().next() {
+            Some(c) => format!("cannot parse {:?} as a date: expected {expected} at position {col}, got {c:?}", self.input).into(),
+            None => format!("cannot parse {:?} as a date: expected {expected} at position {col}, got end of input", self.input).into()
+        }
+    }
+    fn peek(&self) -> Option<char> {
+        self.input[self.idx..].chars().next()
+    }
+    fn literal(&mut self, c: char) -> Result<(), Error> {
+        if self.peek() != Some(c) {
+            return Err(self.error(&format!("{c:?}")))
+        }
+        self.idx += c.len_utf8();
+        Ok(())
+    }
+    fn digits(&mut self, min: usize, max: usize, name: &str) -> Result<i64, Error> {
+        let rest = &self.input[self.idx..];
+        let len = rest.chars().take(max).take_while(|c| c.is_ascii_digit()).count();
+        if len < min {
+            return Err(self.error(name))
+        }
+        self.idx += len;
+        rest[..len].parse().map_err(|_| self.error(name))
+    }
+    fn signed(&mut self, name: &str) -> Result<i64, Error> {
+        let negative = match self.peek() {
+            Some('-') => { self.idx += 1; true }
+            Some('+') => { self.idx += 1; false }
+            _ => false
+        };
+        let value = self.digits(1, 9, name)?;
+        Ok(if negative { -value } else { value })
+    }
+    fn name(&mut self, names: &[&str], expected: &str) -> Result<usize, Error> {
+        let rest = self.input[self.idx..].to_lowercase();
+        for (idx, name) in names.iter().enumerate() {
+            let name = name.to_lowercase();
+            for candidate in [&name[..], &name[..3]] {
+                if rest.starts_with(candidate) {
+                    self.idx += candidate.len();
+                    return Ok(idx)
+                }
+            }
+        }
+        Err(self.error(expected))
+    }
+    fn offset(&mut self) -> Result<i32, Error> {
+        let negative = match self.peek() {
+            Some('Z' | 'z') => {
+                self.idx += 1;
+                return Ok(0)
+            }
+            Some('+') => false,
+            Some('-') => true,
+            _ => return Err(self.error("utc offset"))
+        };
+        self.idx += 1;
+        let hours = self.digits(2, 2, "offset hours")?;
+        if self.peek() == Some(':') {
+            self.idx += 1;
+        }
+        let minutes = self.digits(2, 2, "offset minutes")?;
+        if hours > 23 || minutes > 59 {
+            return Err(format!("invalid utc offset in {:?}", self.input).into())
+        }
+        let offset = (hours * 3600 + minutes * 60) as i32;
+        Ok(if negative { -offset } else { offset })
+    }
+}
+
+pub fn parse(input: &str, pattern: &str) -> Result<Date, Error> {
+    let mut parser = Parser { input, idx: 0 };
+    parse_with(&mut parser, pattern)
+}
+fn parse_with(parser: &mut Parser, pattern: &str) -> Result<Date, Error> {
+    let input = parser.input;
+    let mut civil = Civil { year: 1970, month: 1, day: 1, hour: 0, minute: 0, second: 0, millisecond: 0, weekday: 0, yearday: 0 };
+    let mut offset = 0;
+    let mut pm = None;
+    let mut timestamp = None;
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            parser.literal(c)?;
+            continue
+        }
+        let Some(spec) = chars.next() else {
+            return Err("incomplete format specifier at end of pattern".into())
+        };
+        match spec {
+            'Y' => civil.year = parser.signed("year")?,
+            'y' => {
+                let year = parser.digits(2, 2, "two-digit year")?;
+                civil.year = if year < 69 { 2000 + year } else { 1900 + year };
+            }
+            'm' => civil.month = parser.digits(1, 2, "month")? as u32,
+            'd' | 'e' => {
+                if spec == 'e' && parser.peek() == Some(' ') {
+                    parser.idx += 1;
+                }
+                civil.day = parser.digits(1, 2, "day")? as u32;
+            }
+            'H' | 'I' => civil.hour = parser.digits(1, 2, "hour")? as u32,
+            'M' => civil.minute = parser.digits(1, 2, "minute")? as u32,
+            'S' => civil.second = parser.digits(1, 2, "second")? as u32,
+            'f' => {
+                let start = parser.idx;
+                let fraction = parser.digits(1, 9, "fraction of a second")?;
+                let len = parser.idx - start;
+                civil.millisecond = (fraction * 1000 / 10i64.pow(len as u32)) as u32;
+            }
+            'p' => {
+                let rest = parser.input[parser.idx..].to_lowercase();
+                if rest.starts_with("am") {
+                    pm = Some(false);
+                } else if rest.starts_with("pm") {
+                    pm = Some(true);
+                } else {
+                    return Err(parser.error("AM or PM"))
+                }
+                parser.idx += 2;
+            }
+            'j' => {
+                parser.digits(1, 3, "day of the year")?;
+            }
+            'a' | 'A' => {
+                parser.name(&WEEKDAYS, "weekday name")?;
+            }
+            'b' | 'B' => civil.month = parser.name(&MONTHS, "month name")? as u32 + 1,
+            'z' | 'Z' => offset = parser.offset()?,
+            's' => {
+                let negative = parser.peek() == Some('-');
+                if negative {
+                    parser.idx += 1;
+                }
+                let value = parser.digits(1, 15, "unix timestamp")?;
+                timestamp = Some(if negative { -value } else { value });
+            }
+            'F' => {
+                civil.year = parser.signed("year")?;
+                parser.literal('-')?;
+                civil.month = parser.digits(1, 2, "month")? as u32;
+                parser.literal('-')?;
+                civil.day = parser.digits(1, 2, "day")? as u32;
+            }
+            'T' => {
+                civil.hour = parser.digits(1, 2, "hour")? as u32;
+                parser.literal(':')?;
+                civil.minute = parser.digits(1, 2, "minute")? as u32;
+                parser.literal(':')?;
+                civil.second = parser.digits(1, 2, "second")? as u32;
+            }
+            '%' => parser.literal('%')?,
+            spec => return Err(format!("unknown format specifier %{spec}").into())
+        }
+    }
+    if parser.idx < input.len() {
+        return Err(parser.error("end of input"))
+    }
+    if let Some(timestamp) = timestamp {
+        let Some(date) = timestamp.checked_mul(SECOND).and_then(|timestamp| Date::checked(timestamp, offset)) else {
+            return Err(format!("cannot parse {input:?} as a date: timestamp out of range").into())
+        };
+        return Ok(date)
+    }
+    if let Some(pm) = pm {
+        if civil.hour == 0 || civil.hour > 12 {
+            return Err(format!("cannot parse {input:?} as a date: hour {} is not a 12-hour clock hour", civil.hour).into())
+        }
+        civil.hour = civil.hour % 12 + if pm { 12 } else { 0 };
+    }
+    match Date::from_civil(&civil, offset) {
+        Some(date) => Ok(date),
+        None => Err(format!("cannot parse {input:?} as a date: {:04}-{:02}-{:02} {:02}:{:02}:{:02} is not a valid date", civil.year, civil.month, civil.day, civil.hour, civil.minute, civil.second).into())
+    }
+}
+
+pub fn _now(_: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_err(|err| err.to_string())?;
+    Ok(Some(Value::Date(Date::new(now.as_millis() as i64, 0))))
+}
+pub fn _date(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let year = match args.first() {
+        Some(Value::Int(year)) => *year,
+        Some(value) => return Err(format!("expected int for argument #1, got {}", Type::from(value.clone())).into()),
+        None => return Err("expected int for argument #1, got none".into())
+    };
+    let mut fields = [1u32, 1, 0, 0, 0, 0];
+    for (idx, field) in fields.iter_mut().enumerate() {
+        let value = int(&args, idx + 1, *field as i64)?;
+        *field = u32::try_from(value).map_err(|_| format!("expected non-negative int for argument #{}, got {value}", idx + 2))?;
+    }
+    let [month, day, hour, minute, second, millisecond] = fields;
+    let civil = Civil { year, month, day, hour, minute, second, millisecond, weekday: 0, yearday: 0 };
+    let offset = offset(&args, 7)?;
+    match Date::from_civil(&civil, offset) {
+        Some(date) => Ok(Some(Value::Date(date))),
+        None => Err(format!("{year:04}-{month:02}-{day:02} {hour:02}:{minute:02}:{second:02}.{millisecond:03} is not a valid date").into())
+    }
+}
+pub fn _parse(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let input = string(&args, 0)?;
+    if args.len() >= 2 {
+        return Ok(Some(Value::Date(parse(input, string(&args, 1)?)?)))
+    }
+    let mut error: Option<(usize, Error)> = None;
+    for pattern in ISO_PATTERNS {
+        let mut parser = Parser { input, idx: 0 };
+        match parse_with(&mut parser, pattern) {
+            Ok(date) => return Ok(Some(Value::Date(date))),
+            Err(err) => if error.as_ref().is_none_or(|(idx, _)| parser.idx >= *idx) {
+                error = Some((parser.idx, err));
+            }
+        }
+    }
+    Err(error.map(|(_, err)| err).unwrap_or_else(|| format!("cannot parse {input:?} as a date").into()))
+}
+pub fn _format(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let date = date(&args, 0)?;
+    if args.len() < 2 {
+        return Ok(Some(Value::String(date.to_string())))
+    }
+    Ok(Some(Value::String(format(&date, string(&args, 1)?)?)))
+}
+pub fn _utc(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let date = date(&args, 0)?;
+    Ok(Some(Value::Date(Date::new(date.timestamp, 0))))
+}
+pub fn _offset(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let date = date(&args, 0)?;
+    if args.len() < 2 {
+        return Ok(Some(Value::Duration(date.offset as i64 * SECOND)))
+    }
+    match Date::checked(date.timestamp, offset(&args, 1)?) {
+        Some(date) => Ok(Some(Value::Date(date))),
+        None => Err("date out of range".into())
+    }
+}
+pub fn _parts(args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let date = date(&args, 0)?;
+    let civil = date.civil();
+    let parts = Object::default()
+        .insert("year", Value::Int(civil.year))
+        .insert("month", Value::Int(civil.month as i64))
+        .insert("day", Value::Int(civil.day as i64))
+        .insert("hour", Value::Int(civil.hour as i64))
+        .insert("minute", Value::Int(civil.minute as i64))
+        .insert("second", Value::Int(civil.second as i64))
+        .insert("millisecond", Value::Int(civil.millisecond as i64))
+        .insert("weekday", Value::String(WEEKDAYS[civil.weekday as usize].to_string()))
+        .insert("yearday", Value::Int(civil.yearday as i64))
+        .insert("offset", Value::Duration(date.offset as i64 * SECOND));
+    Ok(Some(Value::Object(program.objects.create(parts))))
+}
+pub fn _timestamp(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let date = date(&args, 0)?;
+    Ok(Some(Value::Float(date.timestamp as f64 / SECOND as f64)))
+}
+pub fn _from_timestamp(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let timestamp = match args.first() {
+        Some(Value::Int(v)) => v.checked_mul(SECOND),
+        Some(Value::Float(v)) if v.is_finite() && v.abs() < (i64::MAX / SECOND) as f64 => Some((v * SECOND as f64).round() as i64),
+        Some(Value::Float(_)) => None,
+        Some(value) => return Err(format!("expected number for argument #1, got {}", Type::from(value.clone())).into()),
+        None => return Err("expected number for argument #1, got none".into())
+    };
+    let offset = offset(&args, 1)?;
+    let Some(date) = timestamp.and_then(|timestamp| Date::checked(timestamp, offset)) else {
+        return Err("timestamp out of range".into())
+    };
+    Ok(Some(Value::Date(date)))
+}
+pub fn _seconds(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    match args.first() {
+        Some(Value::Duration(v)) => Ok(Some(Value::Float(*v as f64 / SECOND as f64))),
+        Some(value) => Err(format!("expected duration for argument #1, got {}", Type::from(value.clone())).into()),
+        None => Err("expected duration for argument #1, got none".into())
+    }
+}
+pub fn _duration(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    match args.first() {
+        Some(Value::Int(v)) => match v.checked_mul(SECOND) {
+            Some(v) => Ok(Some(Value::Duration(v))),
+            None => Err("duration out of range".into())
+        }
+        Some(Value::Float(v)) if v.is_finite() && v.abs() < (i64::MAX / SECOND) as f64 => Ok(Some(Value::Duration((v * SECOND as f64).round() as i64))),
+        Some(Value::Float(_)) => Err("duration out of range".into()),
+        Some(Value::String(string)) => {
+            let tokens = lex(string.clone()).map_err(|err| format!("invalid duration {string:?}: {}", err.value))?;
+            match tokens.as_slice() {
+                [token] => match token.value {
+                    Token::Duration(v) => Ok(Some(Value::Duration(v))),
+                    _ => Err(format!("invalid duration {string:?}").into())
+                }
+                _ => Err(format!("invalid duration {string:?}").into())
+            }
+        }
+        Some(value) => Err(format!("expected number or string for argument #1, got {}", Type::from(value.clone())).into()),
+        None => Err("expected number or string for argument #1, got none".into())
+    }
+}
@@ -0,0 +1,282 @@
+use std::{cmp::Ordering, collections::HashSet};
+use crate::{
+    structure::{
+        program::Program,
+        value::*,
+        error::Error
+    },
+    implementation::evaluate::call
+};
+
+pub fn module(program: &mut Program) -> Object {
+    Object::default()
+        .insert("push", program.native("vector.push", &["vector", "value"], "returns the vector with the value appended", _push))
+        .insert("pop", program.native("vector.pop", &["vector"], "returns the vector without its last element, see vector.last to read it", _pop))
+        .insert("last", program.native("vector.last", &["vector"], "returns the last element or none if the vector is empty", _last))
+        .insert("insert", program.native("vector.insert", &["vector", "index", "value"], "returns the vector with the value inserted at the index", _insert))
+        .insert("remove", program.native("vector.remove", &["vector", "index"], "returns the vector without the element at the index", _remove))
+        .insert("len", program.native("vector.len", &["vector"], "returns the number of elements", _len))
+        .insert("sort", program.native("vector.sort", &["vector"], "returns the elements in ascending order", _sort))
+        .insert("reverse", program.native("vector.reverse", &["vector"], "returns the elements in reverse order", _reverse))
+        .insert("contains", program.native("vector.contains", &["vector", "value"], "checks whether the vector contains the value", _contains))
+        .insert("index_of", program.native("vector.index_of", &["vector", "value"], "returns the index of the first matching element or none", _index_of))
+        .insert("unique", program.native("vector.unique", &["vector"], "returns the elements without duplicates, keeping the first occurrence", _unique))
+        .insert("flatten", program.native("vector.flatten", &["vector"], "concatenates nested vectors one level deep", _flatten))
+        .insert("zip", program.native("vector.zip", &["left", "right"], "pairs up elements of two vectors", _zip))
+        .insert("enumerate", program.native("vector.enumerate", &["vector"], "pairs each element with its index", _enumerate))
+        .insert("sum", program.native("vector.sum", &["vector"], "adds up all elements", _sum))
+        .insert("product", program.native("vector.product", &["vector"], "multiplies all elements", _product))
+        .insert("map", program.native("vector.map", &["vector", "function"], "applies the function to every element", _map))
+        .insert("filter", program.native("vector.filter", &["vector", "function"], "keeps the elements for which the function returns true", _filter))
+        .insert("reduce", program.native("vector.reduce", &["vector", "function", "initial?"], "folds the elements into a single value", _reduce))
+        .insert("any", program.native("vector.any", &["vector", "function"], "checks whether the function returns true for any element", _any))
+        .insert("all", program.native("vector.all", &["vector", "function"], "checks whether the function returns true for every element", _all))
+        .insert("sort_by", program.native("vector.sort_by", &["vector", "key"], "sorts the elements by the result of the key function", _sort_by))
+}
+
+pub fn vector(args: &mut [Value], idx: usize) -> Result<Vec<Value>, Error> {
+    match args.get_mut(idx) {
+        Some(Value::Vector(values)) => Ok(std::mem::take(values)),
+        Some(value) => Err(format!("expected vector for argument #{}, got {}", idx + 1, Type::from(value.clone())).into()),
+        None => Err(format!("expected vector for argument #{}, got none", idx + 1).into())
+    }
+}
+fn index(args: &[Value], idx: usize, len: usize) -> Result<usize, Error> {
+    let index = match args.get(idx) {
+        Some(Value::Int(v)) => *v,
+        Some(value) => return Err(format!("expected int for argument #{}, got {}", idx + 1, Type::from(value.clone())).into()),
+        None => return Err(format!("expected int for argument #{}, got none", idx + 1).into())
+    };
+    let resolved = if index < 0 { index + len as i64 } else { index };
+    if resolved < 0 || resolved as usize > len {
+        return Err(format!("index {index} out of range for vector of length {len}").into())
+    }
+    Ok(resolved as usize)
+}
+fn function(args: &[Value], idx: usize) -> Result<Value, Error> {
+    match args.get(idx) {
+        Some(value @ (Value::Function(..) | Value::Closure(_))) => Ok(value.clone()),
+        Some(value) => Err(format!("expected function for argument #{}, got {}", idx + 1, Type::from(value.clone())).into()),
+        None => Err(format!("expected function for argument #{}, got none", idx + 1).into())
+    }
+}
+pub fn apply(func: &Value, args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let pos = program.pos.clone();
+    call(func.clone(), args, program, pos).map_err(Error::from)
+}
+fn predicate(func: &Value, value: Value, program: &mut Program) -> Result<bool, Error> {
+    match apply(func, vec![value], program)? {
+        Some(Value::Boolean(v)) => Ok(v),
+        Some(value) => Err(format!("expected boolean from function, got {}", Type::from(value)).into()),
+        None => Err("expected boolean from function, got none".into())
+    }
+}
+pub fn compare(left: &Value, right: &Value) -> Result<Ordering, Error> {
+    match (left, right) {
+        (Value::Int(v1), Value::Int(v2)) => Ok(v1.cmp(v2)),
+        (Value::Float(v1), Value::Float(v2)) => Ok(v1.total_cmp(v2)),
+        (Value::Int(v1), Value::Float(v2)) => Ok((*v1 as f64).total_cmp(v2)),
+        (Value::Float(v1), Value::Int(v2)) => Ok(v1.total_cmp(&(*v2 as f64))),
+        (Value::String(v1), Value::String(v2)) => Ok(v1.cmp(v2)),
+        (Value::Boolean(v1), Value::Boolean(v2)) => Ok(v1.cmp(v2)),
+        (Value::Vector(v1), Value::Vector(v2)) => {
+            for (left, right) in v1.iter().zip(v2.iter()) {
+                match compare(left, right)? {
+                    Ordering::Equal => continue,
+                    ordering => return Ok(ordering)
+                }
+            }
+            Ok(v1.len().cmp(&v2.len()))
+        }
+        (left, right) => Err(format!("cannot compare {} with {}", Type::from(left.clone()), Type::from(right.clone())).into())
+    }
+}
+fn sort<T>(values: &mut [(Value, T)]) -> Result<(), Error> {
+    let mut error = None;
+    values.sort_by(|(left, _), (right, _)| compare(left, right).unwrap_or_else(|err| {
+        error.get_or_insert(err);
+        Ordering::Equal
+    }));
+    match error {
+        Some(err) => Err(err),
+        None => Ok(())
+    }
+}
+fn fold(values: Vec<Value>, init: i64, int: fn(i64, i64) -> Option<i64>, float: fn(f64, f64) -> f64) -> Result<Value, Error> {
+    let mut acc = Value::Int(init);
+    for (idx, value) in values.into_iter().enumerate() {
+        acc = match (acc, value) {
+            (Value::Int(v1), Value::Int(v2)) => match int(v1, v2) {
+                Some(v) => Value::Int(v),
+                None => return Err("integer overflow".into())
+            }
+            (Value::Int(v1), Value::Float(v2)) => Value::Float(float(v1 as f64, v2)),
+            (Value::Float(v1), Value::Int(v2)) => Value::Float(float(v1, v2 as f64)),
+            (Value::Float(v1), Value::Float(v2)) => Value::Float(float(v1, v2)),
+            (_, value) => return Err(format!("expected number at index {idx}, got {}", Type::from(value)).into())
+        };
+    }
+    Ok(acc)
+}
+
+pub fn _push(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let mut values = vector(&mut args, 0)?;
+    values.extend(args.into_iter().skip(1));
+    Ok(Some(Value::Vector(values)))
+}
+pub fn _pop(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let mut values = vector(&mut args, 0)?;
+    if values.pop().is_none() {
+        return Err("cannot pop from an empty vector".into())
+    }
+    Ok(Some(Value::Vector(values)))
+}
+pub fn _last(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    Ok(vector(&mut args, 0)?.pop())
+}
+pub fn _insert(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let mut values = vector(&mut args, 0)?;
+    let idx = index(&args, 1, values.len())?;
+    let Some(value) = args.get(2) else {
+        return Err("expected value for argument #3, got none".into())
+    };
+    values.insert(idx, value.clone());
+    Ok(Some(Value::Vector(values)))
+}
+pub fn _remove(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let mut values = vector(&mut args, 0)?;
+    let idx = index(&args, 1, values.len())?;
+    if idx == values.len() {
+        return Err(format!("index {idx} out of range for vector of length {}", values.len()).into())
+    }
+    values.remove(idx);
+    Ok(Some(Value::Vector(values)))
+}
+pub fn _len(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    Ok(Some(Value::Int(vector(&mut args, 0)?.len() as i64)))
+}
+pub fn _sort(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let mut values: Vec<(Value, ())> = vector(&mut args, 0)?.into_iter().map(|value| (value, ())).collect();
+    sort(&mut values)?;
+    Ok(Some(Value::Vector(values.into_iter().map(|(value, _)| value).collect())))
+}
+pub fn _reverse(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let mut values = vector(&mut args, 0)?;
+    values.reverse();
+    Ok(Some(Value::Vector(values)))
+}
+pub fn _contains(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let values = vector(&mut args, 0)?;
+    let Some(value) = args.get(1) else {
+        return Err("expected value for argument #2, got none".into())
+    };
+    Ok(Some(Value::Boolean(values.contains(value))))
+}
+pub fn _index_of(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let values = vector(&mut args, 0)?;
+    let Some(value) = args.get(1) else {
+        return Err("expected value for argument #2, got none".into())
+    };
+    Ok(values.iter().position(|other| other == value).map(|idx| Value::Int(idx as i64)))
+}
+pub fn _unique(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let values = vector(&mut args, 0)?;
+    let mut seen = HashSet::new();
+    Ok(Some(Value::Vector(values.into_iter().filter(|value| seen.insert(value.clone())).collect())))
+}
+pub fn _flatten(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let values = vector(&mut args, 0)?;
+    let mut flat = vec![];
+    for value in values {
+        match value {
+            Value::Vector(values) => flat.extend(values),
+            value => flat.push(value)
+        }
+    }
+    Ok(Some(Value::Vector(flat)))
+}
+pub fn _zip(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let left = vector(&mut args, 0)?;
+    let right = vector(&mut args, 1)?;
+    Ok(Some(Value::Vector(left.into_iter().zip(right).map(|(left, right)| Value::Vector(vec![left, right])).collect())))
+}
+pub fn _enumerate(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let values = vector(&mut args, 0)?;
+    Ok(Some(Value::Vector(values.into_iter().enumerate().map(|(idx, value)| Value::Vector(vec![Value::Int(idx as i64), value])).collect())))
+}
+pub fn _sum(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    Ok(Some(fold(vector(&mut args, 0)?, 0, i64::checked_add, |v1, v2| v1 + v2)?))
+}
+pub fn _product(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    Ok(Some(fold(vector(&mut args, 0)?, 1, i64::checked_mul, |v1, v2| v1 * v2)?))
+}
+pub fn _map(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let values = vector(&mut args, 0)?;
+    let func = function(&args, 1)?;
+    let mut mapped = vec![];
+    for value in values {
+        let Some(value) = apply(&func, vec![value], program)? else {
+            return Err("expected value from map function, got none".into())
+        };
+        mapped.push(value);
+    }
+    Ok(Some(Value::Vector(mapped)))
+}
+pub fn _filter(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let values = vector(&mut args, 0)?;
+    let func = function(&args, 1)?;
+    let mut filtered = vec![];
+    for value in values {
+        if predicate(&func, value.clone(), program)? {
+            filtered.push(value);
+        }
+    }
+    Ok(Some(Value::Vector(filtered)))
+}
+pub fn _reduce(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let mut values = vector(&mut args, 0)?.into_iter();
+    let func = function(&args, 1)?;
+    let Some(mut acc) = args.get(2).cloned().or_else(|| values.next()) else {
+        return Err("cannot reduce an empty vector without an initial value".into())
+    };
+    for value in values {
+        let Some(value) = apply(&func, vec![acc, value], program)? else {
+            return Err("expected value from reduce function, got none".into())
+        };
+        acc = value;
+    }
+    Ok(Some(acc))
+}
+pub fn _any(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let values = vector(&mut args, 0)?;
+    let func = function(&args, 1)?;
+    for value in values {
+        if predicate(&func, value, program)? {
+            return Ok(Some(Value::Boolean(true)))
+        }
+    }
+    Ok(Some(Value::Boolean(false)))
+}
+pub fn _all(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let values = vector(&mut args, 0)?;
+    let func = function(&args, 1)?;
+    for value in values {
+        if !predicate(&func, value, program)? {
+            return Ok(Some(Value::Boolean(false)))
+        }
+    }
+    Ok(Some(Value::Boolean(true)))
+}
+pub fn _sort_by(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let values = vector(&mut args, 0)?;
+    let func = function(&args, 1)?;
+    let mut keyed = vec![];
+    for value in values {
+        let Some(key) = apply(&func, vec![value.clone()], program)? else {
+            return Err("expected value from key function, got none".into())
+        };
+        keyed.push((key, value));
+    }
+    sort(&mut keyed)?;
+    Ok(Some(Value::Vector(keyed.into_iter().map(|(_, value)| value).collect())))
+}
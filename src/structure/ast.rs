@@ -1,35 +1,50 @@
 use crate::tools::parse::*;
+use std::fmt::Display;
 use super::{
     position::{
         Located,
         Position
     },
-    tokens::Token
+    tokens::Token,
+    value::Type
 };
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Atom {
-    Int(i64), Float(f64), Boolean(bool), None, String(String),
+    Int(i64), Float(f64), Boolean(bool), None, String(String), Duration(i64), Regex(String),
     Ident(String),
     Expression(Box<Located<Expression>>),
     Vector(Vec<Located<Expression>>),
+    Map(Vec<(Located<Expression>, Located<Expression>)>),
+    Set(Vec<Located<Expression>>),
     // Object(Box<Located<ObjectEntry>>),
     Field {
         head: Box<Located<Self>>,
-        field: Located<String>
+        field: Located<String>,
+        optional: bool
     },
     Index {
         head: Box<Located<Self>>,
-        field: Box<Located<Expression>>
+        field: Box<Located<Expression>>,
+        optional: bool
     },
 }
 
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BinaryOperator {
-    Add, Sub, Mul, Div, Pow, Mod
+    Add, Sub, Mul, Div, Pow, Mod,
+    BitOr, BitXor, BitAnd, Shl, Shr,
+    In, Coalesce, Pipe
 }
 pub const BINARY_LAYERS: &[&[BinaryOperator]] = &[
+    &[BinaryOperator::Pipe],
+    &[BinaryOperator::Coalesce],
+    &[BinaryOperator::In],
+    &[BinaryOperator::BitOr],
+    &[BinaryOperator::BitXor],
+    &[BinaryOperator::BitAnd],
+    &[BinaryOperator::Shl, BinaryOperator::Shr],
     &[BinaryOperator::Add, BinaryOperator::Sub],
     &[BinaryOperator::Mul, BinaryOperator::Div, BinaryOperator::Mod],
     &[BinaryOperator::Pow],
@@ -46,16 +61,24 @@ impl BinaryOperator {
             Token::Div => Some(Self::Div),
             Token::Pow => Some(Self::Pow),
             Token::Mod => Some(Self::Mod),
+            Token::BitOr => Some(Self::BitOr),
+            Token::BitAnd => Some(Self::BitAnd),
+            Token::BitXor | Token::Xor => Some(Self::BitXor),
+            Token::Shl => Some(Self::Shl),
+            Token::Shr => Some(Self::Shr),
+            Token::In => Some(Self::In),
+            Token::Coalesce => Some(Self::Coalesce),
+            Token::Pipe => Some(Self::Pipe),
             _ => None
         }
     }
 }
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UnaryOperator {
-    Neg, Not
+    Neg, Not, BitNot
 }
 pub const UNARY_LAYERS: &[&[UnaryOperator]] = &[
-    &[UnaryOperator::Neg],
+    &[UnaryOperator::Neg, UnaryOperator::BitNot],
 ];
 impl UnaryOperator {
     pub fn layer(layer: usize) -> Option<&'static [Self]> {
@@ -64,18 +87,99 @@ impl UnaryOperator {
     pub fn token(token: &Token) -> Option<Self> {
         match token {
             Token::Sub => Some(Self::Neg),
+            Token::BitXor => Some(Self::BitNot),
             _ => None
         }
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum Annotation {
+    Any, None,
+    Type(Type),
+    Vector(Box<Self>),
+    Union(Vec<Self>),
+    Function(Vec<Self>, Box<Self>)
+}
+impl Display for Annotation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Annotation::Any => write!(f, "any"),
+            Annotation::None => write!(f, "none"),
+            Annotation::Type(typ) => write!(f, "{typ}"),
+            Annotation::Vector(typ) => write!(f, "[{typ}]"),
+            Annotation::Union(types) => write!(f, "{}", types.iter().map(|typ| typ.to_string()).collect::<Vec<String>>().join(" | ")),
+            Annotation::Function(params, ret) => write!(f, "fn({}) -> {ret}", params.iter().map(|typ| typ.to_string()).collect::<Vec<String>>().join(", ")),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Args(pub Vec<Located<Expression>>);
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block(pub Vec<Located<Expression>>);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk(pub Vec<Located<Expression>>);
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Expression {
     Atom(Atom),
     Binary { op: BinaryOperator, left: Box<Located<Self>>, right: Box<Located<Self>> },
     Unary { op: UnaryOperator, right: Box<Located<Self>> },
-    Call { head: Located<Atom>, args: Located<Args> }
+    Call { head: Located<Atom>, args: Located<Args> },
+    Try {
+        body: Box<Located<Self>>,
+        catch: Option<(Located<String>, Located<Block>)>,
+        finally: Option<Located<Block>>
+    },
+    Import { name: Option<Located<String>>, path: Located<String> },
+    Let { ident: Located<String>, annotation: Option<Located<Annotation>>, expr: Box<Located<Self>>, constant: bool },
+    Assign { target: Located<Atom>, expr: Box<Located<Self>> },
+    Function {
+        params: Vec<(Located<String>, Option<Located<Annotation>>)>,
+        ret: Option<Located<Annotation>>,
+        body: Located<Block>,
+        generator: bool
+    },
+    For { ident: Located<String>, iter: Box<Located<Self>>, body: Located<Block> },
+    Yield(Box<Located<Self>>)
+}
+
+impl Atom {
+    pub fn yields(&self) -> bool {
+        match self {
+            Atom::Expression(expr) => expr.value.yields(),
+            Atom::Vector(exprs) | Atom::Set(exprs) => exprs.iter().any(|expr| expr.value.yields()),
+            Atom::Map(entries) => entries.iter().any(|(key, value)| key.value.yields() || value.value.yields()),
+            Atom::Field { head, field: _, optional: _ } => head.value.yields(),
+            Atom::Index { head, field, optional: _ } => head.value.yields() || field.value.yields(),
+            _ => false
+        }
+    }
+}
+impl Block {
+    pub fn yields(&self) -> bool {
+        self.0.iter().any(|expr| expr.value.yields())
+    }
+}
+impl Expression {
+    pub fn yields(&self) -> bool {
+        match self {
+            Expression::Atom(atom) => atom.yields(),
+            Expression::Binary { op: _, left, right } => left.value.yields() || right.value.yields(),
+            Expression::Unary { op: _, right } => right.value.yields(),
+            Expression::Call { head, args } => head.value.yields() || args.value.0.iter().any(|arg| arg.value.yields()),
+            Expression::Try { body, catch, finally } => body.value.yields()
+                || catch.as_ref().is_some_and(|(_, block)| block.value.yields())
+                || finally.as_ref().is_some_and(|block| block.value.yields()),
+            Expression::Import { name: _, path: _ } => false,
+            Expression::Let { ident: _, annotation: _, expr, constant: _ } => expr.value.yields(),
+            Expression::Assign { target, expr } => target.value.yields() || expr.value.yields(),
+            Expression::Function { params: _, ret: _, body: _, generator: _ } => false,
+            Expression::For { ident: _, iter, body } => iter.value.yields() || body.value.yields(),
+            Expression::Yield(_) => true,
+        }
+    }
 }
@@ -0,0 +1,63 @@
+use std::fmt::Display;
+
+use super::position::Located;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    Runtime(String),
+    User(String),
+    Exit(i32),
+    /// an error raised inside a callback, keeping its position while it passes through a native function
+    At(Box<Located<Error>>),
+}
+impl Error {
+    pub fn map<F: FnOnce(String) -> String>(self, f: F) -> Self {
+        match self {
+            Error::Runtime(msg) => Error::Runtime(f(msg)),
+            Error::User(msg) => Error::User(f(msg)),
+            Error::Exit(code) => Error::Exit(code),
+            Error::At(err) => Error::At(Box::new(err.map(|err| err.map(f)))),
+        }
+    }
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::Runtime(_) => "runtime",
+            Error::User(_) => "error",
+            Error::Exit(_) => "exit",
+            Error::At(err) => err.value.kind(),
+        }
+    }
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Runtime(msg) => write!(f, "{msg}"),
+            Error::User(msg) => write!(f, "{msg}"),
+            Error::Exit(code) => write!(f, "exit with status {code}"),
+            Error::At(err) => err.value.fmt(f),
+        }
+    }
+}
+impl From<String> for Error {
+    fn from(msg: String) -> Self {
+        Self::Runtime(msg)
+    }
+}
+impl From<&str> for Error {
+    fn from(msg: &str) -> Self {
+        Self::Runtime(msg.to_string())
+    }
+}
+impl From<Located<Error>> for Error {
+    fn from(err: Located<Error>) -> Self {
+        match err.value {
+            Error::At(err) => Self::At(err),
+            value => Self::At(Box::new(Located::new(value, err.pos)))
+        }
+    }
+}
+impl From<Located<String>> for Located<Error> {
+    fn from(err: Located<String>) -> Self {
+        err.map(Error::from)
+    }
+}
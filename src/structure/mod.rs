@@ -2,4 +2,7 @@ pub mod position;
 pub mod tokens;
 pub mod ast;
 pub mod value;
-pub mod program;
\ No newline at end of file
+pub mod program;
+pub mod error;
+pub mod time;
+pub mod native;
\ No newline at end of file
@@ -0,0 +1,191 @@
+use std::{collections::{HashMap, HashSet}, rc::Rc};
+use crate::structure::{
+    program::{Program, NativeClosure},
+    value::{Value, Type},
+    time::Date,
+    error::Error
+};
+
+pub trait FromValue: Sized {
+    fn expected() -> String;
+    /// whether the argument may be left out
+    fn optional() -> bool {
+        false
+    }
+    fn from_value(value: Option<Value>) -> Result<Self, Option<Value>>;
+}
+pub trait IntoValue {
+    fn into_value(self) -> Option<Value>;
+}
+pub trait NativeResult {
+    fn into_result(self) -> Result<Option<Value>, Error>;
+}
+pub trait Typed<Args> {
+    fn native(self) -> Rc<NativeClosure>;
+}
+
+macro_rules! convert {
+    ($typ:ty, $name:literal, $pattern:pat => $value:expr, $into:expr) => {
+        impl FromValue for $typ {
+            fn expected() -> String {
+                $name.into()
+            }
+            fn from_value(value: Option<Value>) -> Result<Self, Option<Value>> {
+                match value {
+                    Some($pattern) => Ok($value),
+                    value => Err(value)
+                }
+            }
+        }
+        impl IntoValue for $typ {
+            fn into_value(self) -> Option<Value> {
+                #[allow(clippy::redundant_closure_call)]
+                Some(($into)(self))
+            }
+        }
+    };
+}
+convert!(i64, "int", Value::Int(v) => v, Value::Int);
+convert!(bool, "boolean", Value::Boolean(v) => v, Value::Boolean);
+convert!(String, "string", Value::String(v) => v, Value::String);
+convert!(Date, "date", Value::Date(v) => v, Value::Date);
+convert!(HashMap<Value, Value>, "map", Value::Map(v) => v, Value::Map);
+convert!(HashSet<Value>, "set", Value::Set(v) => v, Value::Set);
+
+impl FromValue for f64 {
+    fn expected() -> String {
+        "number".into()
+    }
+    fn from_value(value: Option<Value>) -> Result<Self, Option<Value>> {
+        match value {
+            Some(Value::Int(v)) => Ok(v as f64),
+            Some(Value::Float(v)) => Ok(v),
+            value => Err(value)
+        }
+    }
+}
+impl IntoValue for f64 {
+    fn into_value(self) -> Option<Value> {
+        Some(Value::Float(self))
+    }
+}
+impl FromValue for Value {
+    fn expected() -> String {
+        "value".into()
+    }
+    fn from_value(value: Option<Value>) -> Result<Self, Option<Value>> {
+        value.ok_or(None)
+    }
+}
+impl IntoValue for Value {
+    fn into_value(self) -> Option<Value> {
+        Some(self)
+    }
+}
+impl<T: FromValue> FromValue for Option<T> {
+    fn expected() -> String {
+        format!("{} or none", T::expected())
+    }
+    fn optional() -> bool {
+        true
+    }
+    fn from_value(value: Option<Value>) -> Result<Self, Option<Value>> {
+        match value {
+            None => Ok(None),
+            value => T::from_value(value).map(Some)
+        }
+    }
+}
+impl<T: IntoValue> IntoValue for Option<T> {
+    fn into_value(self) -> Option<Value> {
+        self.and_then(T::into_value)
+    }
+}
+impl<T: FromValue> FromValue for Vec<T> {
+    fn expected() -> String {
+        format!("[{}]", T::expected())
+    }
+    fn from_value(value: Option<Value>) -> Result<Self, Option<Value>> {
+        let Some(Value::Vector(values)) = value else {
+            return Err(value)
+        };
+        let mut converted = Vec::with_capacity(values.len());
+        for value in values.iter() {
+            let Ok(value) = T::from_value(Some(value.clone())) else {
+                return Err(Some(Value::Vector(values)))
+            };
+            converted.push(value);
+        }
+        Ok(converted)
+    }
+}
+impl<T: IntoValue> IntoValue for Vec<T> {
+    fn into_value(self) -> Option<Value> {
+        Some(Value::Vector(self.into_iter().filter_map(T::into_value).collect()))
+    }
+}
+impl IntoValue for &str {
+    fn into_value(self) -> Option<Value> {
+        Some(Value::String(self.to_string()))
+    }
+}
+impl IntoValue for () {
+    fn into_value(self) -> Option<Value> {
+        None
+    }
+}
+
+impl<T: IntoValue> NativeResult for T {
+    fn into_result(self) -> Result<Option<Value>, Error> {
+        Ok(self.into_value())
+    }
+}
+impl<T: IntoValue, E: Into<Error>> NativeResult for Result<T, E> {
+    fn into_result(self) -> Result<Option<Value>, Error> {
+        self.map(T::into_value).map_err(E::into)
+    }
+}
+
+pub fn argument<T: FromValue>(value: Option<Value>, idx: usize) -> Result<T, Error> {
+    T::from_value(value).map_err(|value| match value {
+        Some(value) => format!("expected {} for argument #{idx}, got {}", T::expected(), Type::from(value)).into(),
+        None => format!("expected {} for argument #{idx}, got none", T::expected()).into()
+    })
+}
+
+macro_rules! typed {
+    ($($arg:ident),*) => {
+        impl<Func, Ret, $($arg: FromValue),*> Typed<($($arg,)*)> for Func
+        where Func: Fn($($arg),*) -> Ret + 'static, Ret: NativeResult {
+            #[allow(non_snake_case, unused_mut, unused_variables)]
+            fn native(self) -> Rc<NativeClosure> {
+                Rc::new(move |args: Vec<Value>, _: &mut Program| {
+                    let optional: &[bool] = &[$(<$arg>::optional()),*];
+                    let count = optional.len();
+                    let required = optional.iter().rposition(|optional| !optional).map_or(0, |idx| idx + 1);
+                    if args.len() < required || args.len() > count {
+                        return Err(match (required == count, args.len() < required) {
+                            (true, _) => format!("expected {count} arguments, got {}", args.len()),
+                            (false, true) => format!("expected at least {required} arguments, got {}", args.len()),
+                            (false, false) => format!("expected at most {count} arguments, got {}", args.len())
+                        }.into())
+                    }
+                    let mut args = args.into_iter();
+                    let mut idx = 0;
+                    $(
+                        idx += 1;
+                        let $arg = argument::<$arg>(args.next(), idx)?;
+                    )*
+                    self($($arg),*).into_result()
+                })
+            }
+        }
+    };
+}
+typed!();
+typed!(A);
+typed!(A, B);
+typed!(A, B, C);
+typed!(A, B, C, D);
+typed!(A, B, C, D, E);
+typed!(A, B, C, D, E, F);
@@ -1,12 +1,29 @@
-use std::{collections::HashMap, fmt::Debug};
+use std::{collections::{HashMap, HashSet}, fmt::Debug, path::PathBuf, rc::Rc};
 
-use crate::structure::value::{
-    Value,
-    Object
+use crate::{
+    structure::value::{
+        Value,
+        Object,
+        Function,
+        Iter,
+        Meta
+    },
+    stdlib::{self, io::Capabilities, random::Rng, regex::Regex},
+    implementation::evaluate
 };
-use super::{position::Located, value::Type};
+use super::{position::{Located, Position}, value::Type, error::Error, ast::Annotation, native::Typed};
 
-pub type NativeFunction = fn(Vec<Value>, &mut Program) -> Result<Option<Value>, String>;
+pub type NativeFunction = fn(Vec<Value>, &mut Program) -> Result<Option<Value>, Error>;
+
+pub type NativeClosure = dyn Fn(Vec<Value>, &mut Program) -> Result<Option<Value>, Error>;
+
+#[derive(Clone)]
+pub struct Native(pub Rc<NativeClosure>);
+impl Debug for Native {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "native:{:8x?}", Rc::as_ptr(&self.0) as *const ())
+    }
+}
 
 pub struct Map<T>(Vec<T>);
 impl<T> Map<T> {
@@ -18,12 +35,21 @@ impl<T> Map<T> {
         self.0.push(value);
         addr
     }
-    pub fn get(&mut self, addr: usize) -> Option<&T> {
+    pub fn get(&self, addr: usize) -> Option<&T> {
         self.0.get(addr)
     }
     pub fn get_mut(&mut self, addr: usize) -> Option<&mut T> {
         self.0.get_mut(addr)
     }
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    pub fn truncate(&mut self, len: usize) {
+        self.0.truncate(len)
+    }
 }
 impl<T: Debug> Debug for Map<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -42,46 +68,251 @@ impl<T> Default for Map<T> {
 }
 
 #[derive(Debug, Clone, Default)]
-pub struct Program {
+pub struct Scope {
     pub vars: HashMap<String, Value>,
+    pub consts: HashSet<String>,
+    pub types: HashMap<String, Annotation>,
+    pub parent: Option<usize>,
+}
+impl Scope {
+    pub fn new(parent: Option<usize>) -> Self {
+        Self { vars: HashMap::new(), consts: HashSet::new(), types: HashMap::new(), parent }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Program {
+    pub scopes: Map<Scope>,
+    pub scope: usize,
+    pub captured: usize,
     pub objects: Map<Object>,
-    pub native_fns: Map<NativeFunction>,
+    pub native_fns: Map<Native>,
+    pub fns: Map<Function>,
+    pub iterators: Map<Iter>,
+    pub regexes: Map<Regex>,
+    pub modules: HashMap<PathBuf, Value>,
+    pub importing: Vec<PathBuf>,
+    pub capabilities: Capabilities,
+    pub rng: Rng,
+    /// position of the native call being evaluated
+    pub pos: Position,
+}
+impl Default for Program {
+    fn default() -> Self {
+        let mut scopes = Map::new();
+        let scope = scopes.create(Scope::new(None));
+        Self {
+            scopes, scope,
+            captured: scope,
+            objects: Map::default(),
+            native_fns: Map::default(),
+            fns: Map::default(),
+            iterators: Map::default(),
+            regexes: Map::default(),
+            modules: HashMap::default(),
+            importing: vec![],
+            capabilities: Capabilities::default(),
+            rng: Rng::default(),
+            pos: Position::default(),
+        }
+    }
 }
 impl Program {
+    pub fn get(&self, ident: &str) -> Option<&Value> {
+        let mut scope = Some(self.scope);
+        while let Some(addr) = scope {
+            let Some(Scope { vars, consts: _, types: _, parent }) = self.scopes.get(addr) else { break };
+            if let Some(value) = vars.get(ident) {
+                return Some(value)
+            }
+            scope = *parent;
+        }
+        None
+    }
+    pub fn get_mut(&mut self, ident: &str) -> Option<&mut Value> {
+        let mut scope = Some(self.scope);
+        while let Some(addr) = scope {
+            let Scope { vars, consts: _, types: _, parent } = self.scopes.get(addr)?;
+            if vars.contains_key(ident) {
+                return self.scopes.get_mut(addr)?.vars.get_mut(ident)
+            }
+            scope = *parent;
+        }
+        None
+    }
+    pub fn is_const(&self, ident: &str) -> bool {
+        let mut scope = Some(self.scope);
+        while let Some(addr) = scope {
+            let Some(Scope { vars, consts, types: _, parent }) = self.scopes.get(addr) else { break };
+            if vars.contains_key(ident) {
+                return consts.contains(ident)
+            }
+            scope = *parent;
+        }
+        false
+    }
     pub fn set(&mut self, ident: &str, value: Value) -> Option<Value> {
-        self.vars.insert(ident.to_string(), value)
+        self.scopes.get_mut(self.scope)?.vars.insert(ident.to_string(), value)
+    }
+    pub fn set_const(&mut self, ident: &str, value: Value) -> Option<Value> {
+        self.scopes.get_mut(self.scope)?.consts.insert(ident.to_string());
+        self.set(ident, value)
+    }
+    pub fn annotate(&mut self, ident: &str, annotation: Option<Annotation>) {
+        let Some(scope) = self.scopes.get_mut(self.scope) else {
+            return
+        };
+        match annotation {
+            Some(annotation) => scope.types.insert(ident.to_string(), annotation),
+            None => scope.types.remove(ident)
+        };
+    }
+    pub fn define(&mut self, ident: &str, value: Value) -> Result<Option<Value>, Error> {
+        if self.scopes.get(self.scope).is_some_and(|scope| scope.consts.contains(ident)) {
+            return Err(format!("cannot redefine constant {ident:?}").into())
+        }
+        Ok(self.set(ident, value))
+    }
+    pub fn define_const(&mut self, ident: &str, value: Value) -> Result<Option<Value>, Error> {
+        if self.scopes.get(self.scope).is_some_and(|scope| scope.consts.contains(ident)) {
+            return Err(format!("cannot redefine constant {ident:?}").into())
+        }
+        Ok(self.set_const(ident, value))
+    }
+    pub fn assign(&mut self, ident: &str, value: Value) -> Result<Value, Error> {
+        let mut scope = Some(self.scope);
+        while let Some(addr) = scope {
+            let Some(Scope { vars, consts, types: _, parent }) = self.scopes.get_mut(addr) else { break };
+            if let Some(old) = vars.get_mut(ident) {
+                if consts.contains(ident) {
+                    return Err(format!("cannot assign to constant {ident:?}").into())
+                }
+                return Ok(std::mem::replace(old, value))
+            }
+            scope = *parent;
+        }
+        Err(format!("no variable with the name {ident:?} found").into())
+    }
+    pub fn push_scope(&mut self) -> usize {
+        self.enter_scope(self.scope)
+    }
+    pub fn enter_scope(&mut self, parent: usize) -> usize {
+        self.scope = self.scopes.create(Scope::new(Some(parent)));
+        self.scope
     }
-    pub fn new_object(&mut self, ident: &str, value: Object) -> Option<Value> {
+    /// returns to `scope` and reclaims every scope from `mark` on, unless a closure captured one of them
+    pub fn leave_scope(&mut self, scope: usize, mark: usize) {
+        self.scope = scope;
+        if mark > self.captured {
+            self.scopes.truncate(mark);
+        }
+    }
+    /// keeps the current scope and its parents alive for a closure
+    pub fn capture(&mut self) -> usize {
+        self.captured = self.captured.max(self.scope);
+        self.scope
+    }
+    pub fn pop_scope(&mut self) -> Option<usize> {
+        let parent = self.scopes.get(self.scope)?.parent?;
+        self.scope = parent;
+        Some(parent)
+    }
+    pub fn new_object(&mut self, ident: &str, mut value: Object) -> Option<Value> {
+        value.frozen = true;
         let addr = self.objects.create(value);
-        self.set(ident, Value::Object(addr))
+        self.set_const(ident, Value::Object(addr))
+    }
+    pub fn new_fn(&mut self, ident: &str, params: &[&str], doc: &str, value: NativeFunction) -> Option<Value> {
+        let value = self.native(ident, params, doc, value);
+        self.set_const(ident, value)
+    }
+    pub fn native(&mut self, name: &str, params: &[&str], doc: &str, value: NativeFunction) -> Value {
+        self.closure(name, params, doc, value)
+    }
+    pub fn new_closure<F>(&mut self, ident: &str, params: &[&str], doc: &str, value: F) -> Option<Value>
+    where F: Fn(Vec<Value>, &mut Program) -> Result<Option<Value>, Error> + 'static {
+        let value = self.closure(ident, params, doc, value);
+        self.set_const(ident, value)
+    }
+    pub fn closure<F>(&mut self, name: &str, params: &[&str], doc: &str, value: F) -> Value
+    where F: Fn(Vec<Value>, &mut Program) -> Result<Option<Value>, Error> + 'static {
+        let addr = self.native_fns.create(Native(Rc::new(value)));
+        Value::Function(addr, Rc::new(Meta::new(name, params, doc)))
+    }
+    pub fn new_typed<Args, F: Typed<Args>>(&mut self, ident: &str, params: &[&str], doc: &str, value: F) -> Option<Value> {
+        let value = self.typed(ident, params, doc, value);
+        self.set_const(ident, value)
     }
-    pub fn new_fn(&mut self, ident: &str, value: NativeFunction) -> Option<Value> {
-        let addr = self.native_fns.create(value);
-        self.set(ident, Value::Function(addr))
+    pub fn typed<Args, F: Typed<Args>>(&mut self, name: &str, params: &[&str], doc: &str, value: F) -> Value {
+        let addr = self.native_fns.create(Native(value.native()));
+        Value::Function(addr, Rc::new(Meta::new(name, params, doc)))
     }
     pub fn init() -> Self {
-        let mut program = Self::default();
-            program.new_fn("exit", _exit);
-            program.new_fn("set", _set);
-            program.new_fn("abs", _abs);
+        Self::init_with(Capabilities::default())
+    }
+    pub fn init_with(capabilities: Capabilities) -> Self {
+        let mut program = Self { capabilities, ..Self::default() };
+            program.new_fn("exit", &["code?"], "exits the program with the given status code", _exit);
+            program.new_fn("set", &["name", "value"], "defines a variable by name in the current scope", _set);
+            program.new_fn("abs", &["x"], "returns the absolute value of a number", _abs);
+            program.new_fn("error", &["message?"], "raises a user error with the given message", _error);
+            program.new_fn("freeze", &["object"], "makes an object immutable and returns it", _freeze);
+            program.new_fn("keys", &["collection"], "returns the keys of a map or object as a vector", _keys);
+            program.new_fn("values", &["collection"], "returns the values of a map or object as a vector", _values);
+            program.new_fn("entries", &["collection"], "returns the [key, value] pairs of a map or object", _entries);
+            program.new_fn("to_set", &["vector?"], "converts a vector into a set", _to_set);
+            program.new_fn("to_vector", &["set?"], "converts a set into a vector", _to_vector);
+            program.new_fn("help", &["value?"], "returns the documentation of a function or the members of a module", _help);
+            program.new_fn("int", &["value"], "converts a value to an int", _int);
+            program.new_fn("float", &["value"], "converts a value to a float", _float);
+            program.new_fn("str", &["value"], "converts a value to its string representation", _str);
+            program.new_fn("bool", &["value"], "converts a value to a boolean", _bool);
+            program.new_fn("type", &["value"], "returns the type name of a value", _type);
+            program.new_fn("is", &["value", "type"], "checks whether a value has the given type name", _is);
+            program.new_fn("assert", &["condition", "message?"], "fails with an assertion error if the condition is false", _assert);
+            program.new_fn("assert_eq", &["left", "right", "message?"], "fails with an assertion error if the values differ", _assert_eq);
+            program.new_fn("assert_error", &["function"], "calls the function, fails if it succeeds and returns the error message otherwise", _assert_error);
+            let iter = stdlib::iter::module(&mut program);
+            program.new_object("iter", iter);
+            let math = stdlib::math::module(&mut program);
+            program.new_object("math", math);
+            let string = stdlib::string::module(&mut program);
+            program.new_object("string", string);
+            let vector = stdlib::vector::module(&mut program);
+            program.new_object("vector", vector);
+            let json = stdlib::json::module(&mut program);
+            program.new_object("json", json);
+            let random = stdlib::random::module(&mut program);
+            program.new_object("random", random);
+            let time = stdlib::time::module(&mut program);
+            program.new_object("time", time);
+            program.new_fn("now", &[], "returns the current date in UTC", stdlib::time::_now);
+            let regex = stdlib::regex::module(&mut program);
+            program.new_object("re", regex);
+            program.new_fn("regex", &["pattern"], "compiles a pattern into a regex", stdlib::regex::_regex);
+            stdlib::io::register(&mut program);
         program
     }
 }
 
-pub fn _exit(_: Vec<Value>, _: &mut Program) -> Result<Option<Value>, String> {
-    panic!("exit")
+pub fn _exit(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    match args.first() {
+        None => Err(Error::Exit(0)),
+        Some(Value::Int(code)) => Err(Error::Exit((*code).clamp(i32::MIN as i64, i32::MAX as i64) as i32)),
+        Some(value) => Err(format!("expected int for argument #1, got {}", Type::from(value.clone())).into())
+    }
 }
-pub fn _set(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, String> {
+pub fn _set(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
     if args.len() >= 2 {
         let ident = args.remove(0); let Value::String(ident) = ident else {
-            return Err(format!("expected string for argument #1, got {}", Type::from(ident)))
+            return Err(format!("expected string for argument #1, got {}", Type::from(ident)).into())
         };
         let value = args.remove(0);
-        program.set(&ident, value);
+        program.define(&ident, value)?;
     }
     Ok(None)
 }
-pub fn _abs(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, String> {
+pub fn _abs(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
     if args.is_empty() {
         return Ok(None)
     }
@@ -91,4 +322,276 @@ pub fn _abs(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Stri
         Value::Float(v) => Ok(Some(Value::Float(v.abs()))),
         value => Ok(Some(value))
     }
-}
\ No newline at end of file
+}
+pub fn _error(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    if args.is_empty() {
+        return Err(Error::User("error".into()))
+    }
+    Err(Error::User(args.remove(0).to_string()))
+}
+pub fn _assert(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    match args.first() {
+        Some(Value::Boolean(true)) => Ok(None),
+        Some(Value::Boolean(false)) => match args.get(1) {
+            Some(msg) => Err(Error::User(format!("assertion failed: {msg}"))),
+            None => Err(Error::User("assertion failed".into()))
+        }
+        Some(value) => Err(format!("expected boolean for argument #1, got {}", Type::from(value.clone())).into()),
+        None => Err("expected boolean for argument #1, got none".into())
+    }
+}
+pub fn _assert_eq(args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    let left = args.first();
+    let right = args.get(1);
+    if left == right {
+        return Ok(None)
+    }
+    let show = |value: Option<&Value>| value.map(|value| format!("{value:?}")).unwrap_or("none".into());
+    let msg = format!("{} != {}", show(left), show(right));
+    match args.get(2) {
+        Some(note) => Err(Error::User(format!("assertion failed: {msg}: {note}"))),
+        None => Err(Error::User(format!("assertion failed: {msg}")))
+    }
+}
+pub fn _assert_error(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    if args.is_empty() {
+        return Err("expected function for argument #1, got none".into())
+    }
+    let func = args.remove(0);
+    if !matches!(func, Value::Function(..) | Value::Closure(_)) {
+        return Err(format!("expected function for argument #1, got {}", Type::from(func)).into())
+    }
+    let pos = program.pos.clone();
+    match evaluate::call(func, vec![], program, pos) {
+        Ok(_) => Err(Error::User("assertion failed: expected an error".into())),
+        Err(Located { value: Error::Exit(code), pos: _ }) => Err(Error::Exit(code)),
+        Err(Located { value: err, pos: _ }) => Ok(Some(Value::String(err.to_string())))
+    }
+}
+fn describe(value: &Value, program: &Program) -> Option<String> {
+    match value {
+        Value::Function(_, meta) if meta.doc.is_empty() => Some(meta.to_string()),
+        Value::Function(_, meta) => Some(format!("{meta}\n    {}", meta.doc)),
+        Value::Closure(addr) => {
+            let function = program.fns.get(*addr)?;
+            let params = function.params.iter().map(|param| param.value.clone()).collect::<Vec<String>>().join(", ");
+            Some(format!("fn({params})\n    {}", function.signature))
+        }
+        _ => None
+    }
+}
+fn members(vars: &HashMap<String, Value>, program: &Program) -> String {
+    let mut names = vars.keys().collect::<Vec<&String>>();
+    names.sort();
+    names.into_iter().filter_map(|name| match &vars[name] {
+        Value::Object(_) => Some(format!("{name}\n    module, see help({name})")),
+        value => describe(value, program)
+    }).collect::<Vec<String>>().join("\n")
+}
+pub fn _help(args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    let help = match args.first() {
+        None => {
+            let Some(scope) = program.scopes.get(0) else {
+                return Ok(None)
+            };
+            members(&scope.vars, program)
+        }
+        Some(Value::Object(addr)) => {
+            let Some(object) = program.objects.get(*addr) else {
+                return Ok(None)
+            };
+            members(&object.map, program)
+        }
+        Some(value) => match describe(value, program) {
+            Some(help) => help,
+            None => return Err(format!("expected function or object for argument #1, got {}", Type::from(value.clone())).into())
+        }
+    };
+    Ok(Some(Value::String(help)))
+}
+pub fn _freeze(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    if args.is_empty() {
+        return Ok(None)
+    }
+    let value = args.remove(0); let Value::Object(addr) = value else {
+        return Err(format!("expected object for argument #1, got {}", Type::from(value)).into())
+    };
+    if let Some(object) = program.objects.get_mut(addr) {
+        object.frozen = true;
+    }
+    Ok(Some(value))
+}
+pub fn _keys(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    if args.is_empty() {
+        return Ok(None)
+    }
+    match args.remove(0) {
+        Value::Map(map) => Ok(Some(Value::Vector(map.into_keys().collect()))),
+        Value::Object(addr) => {
+            let object = program.objects.get(addr).unwrap();
+            Ok(Some(Value::Vector(object.map.keys().cloned().map(Value::String).collect())))
+        }
+        value => Err(format!("expected map for argument #1, got {}", Type::from(value)).into())
+    }
+}
+pub fn _values(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    if args.is_empty() {
+        return Ok(None)
+    }
+    match args.remove(0) {
+        Value::Map(map) => Ok(Some(Value::Vector(map.into_values().collect()))),
+        Value::Object(addr) => {
+            let object = program.objects.get(addr).unwrap();
+            Ok(Some(Value::Vector(object.map.values().cloned().collect())))
+        }
+        value => Err(format!("expected map for argument #1, got {}", Type::from(value)).into())
+    }
+}
+pub fn _entries(mut args: Vec<Value>, program: &mut Program) -> Result<Option<Value>, Error> {
+    if args.is_empty() {
+        return Ok(None)
+    }
+    match args.remove(0) {
+        Value::Map(map) => Ok(Some(Value::Vector(map.into_iter().map(|(k, v)| Value::Vector(vec![k, v])).collect()))),
+        Value::Object(addr) => {
+            let object = program.objects.get(addr).unwrap();
+            Ok(Some(Value::Vector(object.map.iter().map(|(k, v)| Value::Vector(vec![Value::String(k.clone()), v.clone()])).collect())))
+        }
+        value => Err(format!("expected map for argument #1, got {}", Type::from(value)).into())
+    }
+}
+pub fn _to_set(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    if args.is_empty() {
+        return Ok(Some(Value::Set(HashSet::new())))
+    }
+    match args.remove(0) {
+        Value::Vector(values) => Ok(Some(Value::Set(values.into_iter().collect()))),
+        Value::Set(values) => Ok(Some(Value::Set(values))),
+        value => Err(format!("expected vector for argument #1, got {}", Type::from(value)).into())
+    }
+}
+pub fn _to_vector(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    if args.is_empty() {
+        return Ok(Some(Value::Vector(vec![])))
+    }
+    match args.remove(0) {
+        Value::Set(values) => Ok(Some(Value::Vector(values.into_iter().collect()))),
+        Value::Vector(values) => Ok(Some(Value::Vector(values))),
+        value => Err(format!("expected set for argument #1, got {}", Type::from(value)).into())
+    }
+}
+
+fn parse_error<T: std::str::FromStr>(string: &str, typ: Type) -> Error {
+    if string.trim().is_empty() {
+        return format!("cannot convert empty string to {typ}").into()
+    }
+    let offset = string.len() - string.trim_start().len();
+    let trimmed = string.trim();
+    let digits = trimmed.strip_prefix(['-', '+']).unwrap_or(trimmed);
+    if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
+        return format!("cannot convert {string:?} to {typ}: number out of range").into()
+    }
+    let valid = trimmed.char_indices()
+        .map(|(idx, c)| idx + c.len_utf8())
+        .take_while(|&end| trimmed[..end].parse::<T>().is_ok() || matches!(&trimmed[..end], "-" | "+" | "." | "-." | "+."))
+        .last()
+        .unwrap_or(0);
+    if valid == trimmed.len() {
+        return format!("cannot convert {string:?} to {typ}: unexpected end of string").into()
+    }
+    let col = string[..offset + valid].chars().count() + 1;
+    format!("cannot convert {string:?} to {typ}: unexpected {:?} at position {col}", &trimmed[valid..]).into()
+}
+pub fn _int(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    if args.is_empty() {
+        return Ok(Some(Value::Int(0)))
+    }
+    match args.remove(0) {
+        Value::Int(v) => Ok(Some(Value::Int(v))),
+        Value::Float(v) => {
+            if !v.is_finite() || v < i64::MIN as f64 || v >= i64::MAX as f64 {
+                return Err(format!("cannot convert {v} to int").into())
+            }
+            Ok(Some(Value::Int(v.trunc() as i64)))
+        }
+        Value::Boolean(v) => Ok(Some(Value::Int(v as i64))),
+        Value::String(v) => match v.trim().parse::<i64>() {
+            Ok(v) => Ok(Some(Value::Int(v))),
+            Err(_) => Err(parse_error::<i64>(&v, Type::Int))
+        }
+        value => Err(format!("cannot convert {} to int", Type::from(value)).into())
+    }
+}
+pub fn _float(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    if args.is_empty() {
+        return Ok(Some(Value::Float(0.)))
+    }
+    match args.remove(0) {
+        Value::Int(v) => Ok(Some(Value::Float(v as f64))),
+        Value::Float(v) => Ok(Some(Value::Float(v))),
+        Value::Boolean(v) => Ok(Some(Value::Float(v as i64 as f64))),
+        Value::String(v) => match v.trim().parse::<f64>() {
+            Ok(v) => Ok(Some(Value::Float(v))),
+            Err(_) => Err(parse_error::<f64>(&v, Type::Float))
+        }
+        value => Err(format!("cannot convert {} to float", Type::from(value)).into())
+    }
+}
+pub fn _str(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    if args.is_empty() {
+        return Ok(Some(Value::String(String::new())))
+    }
+    Ok(Some(Value::String(args.remove(0).to_string())))
+}
+pub fn _bool(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    if args.is_empty() {
+        return Ok(Some(Value::Boolean(false)))
+    }
+    match args.remove(0) {
+        Value::Boolean(v) => Ok(Some(Value::Boolean(v))),
+        Value::Int(v) => Ok(Some(Value::Boolean(v != 0))),
+        Value::Float(v) => Ok(Some(Value::Boolean(v != 0.))),
+        Value::String(v) => match v.trim() {
+            "true" => Ok(Some(Value::Boolean(true))),
+            "false" => Ok(Some(Value::Boolean(false))),
+            _ => Err(format!("cannot convert {v:?} to boolean: expected \"true\" or \"false\"").into())
+        }
+        Value::Vector(v) => Ok(Some(Value::Boolean(!v.is_empty()))),
+        Value::Map(v) => Ok(Some(Value::Boolean(!v.is_empty()))),
+        Value::Set(v) => Ok(Some(Value::Boolean(!v.is_empty()))),
+        value => Err(format!("cannot convert {} to boolean", Type::from(value)).into())
+    }
+}
+pub fn _type(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    if args.is_empty() {
+        return Ok(None)
+    }
+    Ok(Some(Value::String(Type::from(args.remove(0)).to_string())))
+}
+pub fn _is(mut args: Vec<Value>, _: &mut Program) -> Result<Option<Value>, Error> {
+    if args.len() < 2 {
+        return Ok(None)
+    }
+    let value = args.remove(0);
+    let typ = args.remove(0); let Value::String(typ) = typ else {
+        return Err(format!("expected string for argument #2, got {}", Type::from(typ)).into())
+    };
+    let typ = match typ.as_str() {
+        "int" => Type::Int,
+        "float" => Type::Float,
+        "bool" | "boolean" => Type::Boolean,
+        "string" => Type::String,
+        "vector" => Type::Vector,
+        "map" => Type::Map,
+        "set" => Type::Set,
+        "object" => Type::Object,
+        "function" => Type::Function,
+        "iterator" => Type::Iterator,
+        "date" => Type::Date,
+        "duration" => Type::Duration,
+        "regex" => Type::Regex,
+        "number" => return Ok(Some(Value::Boolean(matches!(value, Value::Int(_) | Value::Float(_))))),
+        _ => return Err(format!("unknown type {typ:?}").into())
+    };
+    Ok(Some(Value::Boolean(Type::from(value) == typ)))
+}
@@ -0,0 +1,143 @@
+use std::fmt::Display;
+
+pub const MILLISECOND: i64 = 1;
+pub const SECOND: i64 = 1000 * MILLISECOND;
+pub const MINUTE: i64 = 60 * SECOND;
+pub const HOUR: i64 = 60 * MINUTE;
+pub const DAY: i64 = 24 * HOUR;
+pub const WEEK: i64 = 7 * DAY;
+/// dates stay this far inside the i64 range so shifting them by a utc offset cannot overflow
+pub const MAX_TIMESTAMP: i64 = i64::MAX - DAY;
+
+pub fn unit(unit: &str) -> Option<i64> {
+    match unit {
+        "ms" => Some(MILLISECOND),
+        "s" => Some(SECOND),
+        "m" => Some(MINUTE),
+        "h" => Some(HOUR),
+        "d" => Some(DAY),
+        "w" => Some(WEEK),
+        _ => None
+    }
+}
+
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let month = month as i64;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let doe = days.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+pub fn is_leap(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+pub fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Civil {
+    pub year: i64, pub month: u32, pub day: u32,
+    pub hour: u32, pub minute: u32, pub second: u32, pub millisecond: u32,
+    pub weekday: u32, pub yearday: u32
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Date {
+    pub timestamp: i64,
+    pub offset: i32
+}
+impl Date {
+    pub fn new(timestamp: i64, offset: i32) -> Self {
+        Self { timestamp, offset }
+    }
+    pub fn checked(timestamp: i64, offset: i32) -> Option<Self> {
+        if timestamp.checked_abs()? > MAX_TIMESTAMP || (offset as i64 * SECOND).abs() >= DAY {
+            return None
+        }
+        Some(Self::new(timestamp, offset))
+    }
+    pub fn from_civil(civil: &Civil, offset: i32) -> Option<Self> {
+        if civil.year.abs() > 1_000_000_000 || !(1..=12).contains(&civil.month) || civil.day == 0 || civil.day > days_in_month(civil.year, civil.month)
+            || civil.hour > 23 || civil.minute > 59 || civil.second > 59 || civil.millisecond > 999 {
+            return None
+        }
+        let days = days_from_civil(civil.year, civil.month, civil.day);
+        let local = days.checked_mul(DAY)?
+            .checked_add(civil.hour as i64 * HOUR + civil.minute as i64 * MINUTE + civil.second as i64 * SECOND + civil.millisecond as i64)?;
+        Self::checked(local.checked_sub(offset as i64 * SECOND)?, offset)
+    }
+    pub fn civil(&self) -> Civil {
+        let local = self.timestamp + self.offset as i64 * SECOND;
+        let days = local.div_euclid(DAY);
+        let millis = local.rem_euclid(DAY);
+        let (year, month, day) = civil_from_days(days);
+        Civil {
+            year, month, day,
+            hour: (millis / HOUR) as u32,
+            minute: (millis % HOUR / MINUTE) as u32,
+            second: (millis % MINUTE / SECOND) as u32,
+            millisecond: (millis % SECOND) as u32,
+            weekday: (days + 3).rem_euclid(7) as u32,
+            yearday: (days - days_from_civil(year, 1, 1)) as u32 + 1
+        }
+    }
+}
+pub fn offset_string(offset: i32, colon: bool) -> String {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let offset = offset.unsigned_abs();
+    if colon {
+        format!("{sign}{:02}:{:02}", offset / 3600, offset % 3600 / 60)
+    } else {
+        format!("{sign}{:02}{:02}", offset / 3600, offset % 3600 / 60)
+    }
+}
+impl Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let civil = self.civil();
+        write!(f, "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}", civil.year, civil.month, civil.day, civil.hour, civil.minute, civil.second)?;
+        if civil.millisecond != 0 {
+            write!(f, ".{:03}", civil.millisecond)?;
+        }
+        if self.offset == 0 {
+            write!(f, "Z")
+        } else {
+            write!(f, "{}", offset_string(self.offset, true))
+        }
+    }
+}
+
+pub fn format_duration(duration: i64) -> String {
+    if duration == 0 {
+        return "0s".into()
+    }
+    let mut string = if duration < 0 { String::from("-") } else { String::new() };
+    let mut rest = duration.unsigned_abs();
+    for (unit, size) in [("d", DAY), ("h", HOUR), ("m", MINUTE), ("s", SECOND), ("ms", MILLISECOND)] {
+        let size = size as u64;
+        if rest >= size {
+            string.push_str(&format!("{}{unit}", rest / size));
+            rest %= size;
+        }
+    }
+    string
+}
@@ -6,9 +6,13 @@ use crate::tools::lex::*;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token {
-    Int(i64), Float(f64), Boolean(bool), None, String(String),
+    Int(i64), Float(f64), Boolean(bool), None, String(String), Duration(i64), Regex(String),
     Ident(String),
     ExprIn, ExprOut, ArrayIn, ArrayOut, ObjIn, ObjOut,
     Add, Sub, Mul, Div, Pow, Mod,
-    Field, Seperate
+    BitOr, BitXor, BitAnd, Shl, Shr, Xor,
+    Pipe,
+    Field, OptField, Coalesce, Seperate, End, Assign, Colon, Arrow,
+    Try, Catch, Finally, Import, From,
+    Let, Const, Fn, For, In, Yield
 }
\ No newline at end of file
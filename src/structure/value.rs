@@ -3,17 +3,28 @@ use std::{
         Display,
         Debug
     },
-    collections::HashMap
+    collections::{
+        hash_map::DefaultHasher,
+        HashMap,
+        HashSet
+    },
+    hash::{
+        Hash,
+        Hasher
+    },
+    rc::Rc
 };
 
 use crate::structure::{
-    position::Located,
-    program::Program
+    position::{Located, Position},
+    program::Program,
+    ast::{Block, Annotation, Expression},
+    time::{Date, format_duration}
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Type {
-    Int, Float, Boolean, String, Vector, Object, Function
+    Int, Float, Boolean, String, Vector, Map, Set, Object, Function, Iterator, Date, Duration, Regex
 }
 impl From<Value> for Type {
     fn from(value: Value) -> Self {
@@ -23,8 +34,15 @@ impl From<Value> for Type {
             Value::Boolean(_) => Self::Boolean,
             Value::String(_) => Self::String,
             Value::Vector(_) => Self::Vector,
-            Value::Function(_) => Self::Function,
+            Value::Map(_) => Self::Map,
+            Value::Set(_) => Self::Set,
+            Value::Function(..) => Self::Function,
+            Value::Closure(_) => Self::Function,
+            Value::Iterator(_) => Self::Iterator,
             Value::Object(_) => Self::Object,
+            Value::Date(_) => Self::Date,
+            Value::Duration(_) => Self::Duration,
+            Value::Regex(_) => Self::Regex,
         }
     }
 }
@@ -36,8 +54,14 @@ impl Display for Type {
             Type::Boolean => write!(f, "boolean"),
             Type::String => write!(f, "string"),
             Type::Vector => write!(f, "vector"),
+            Type::Map => write!(f, "map"),
+            Type::Set => write!(f, "set"),
             Type::Object => write!(f, "object"),
             Type::Function => write!(f, "function"),
+            Type::Iterator => write!(f, "iterator"),
+            Type::Date => write!(f, "date"),
+            Type::Duration => write!(f, "duration"),
+            Type::Regex => write!(f, "regex"),
         }
     }
 }
@@ -46,6 +70,7 @@ impl Display for Type {
 pub struct Object {
     pub map: HashMap<String, Value>,
     pub meta: HashMap<String, Value>,
+    pub frozen: bool,
 }
 impl Object {
     pub fn insert(mut self, ident: &str, value: Value) -> Self {
@@ -57,13 +82,129 @@ impl Object {
     }
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub params: Vec<Located<String>>,
+    pub body: Located<Block>,
+    pub signature: Annotation,
+    pub scope: usize,
+    pub generator: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Meta {
+    pub name: String,
+    pub params: Vec<String>,
+    pub doc: String,
+}
+impl Meta {
+    pub fn new(name: &str, params: &[&str], doc: &str) -> Self {
+        Self { name: name.to_string(), params: params.iter().map(|param| param.to_string()).collect(), doc: doc.to_string() }
+    }
+}
+impl Display for Meta {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({})", self.name, self.params.join(", "))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Iter {
+    Values(std::vec::IntoIter<Value>),
+    Generator(Vec<Frame>),
+    Map { iter: usize, func: Value },
+    Filter { iter: usize, func: Value },
+    Take { iter: usize, count: usize },
+    Zip(usize, usize),
+}
+
+/// a suspended statement of a generator, resumed by `next`
+#[derive(Debug, Clone)]
+pub enum Frame {
+    Block { exprs: Vec<Located<Expression>>, idx: usize, scope: usize },
+    For { ident: Located<String>, iter: usize, pos: Position, body: Located<Block>, scope: usize },
+    Try { catch: Option<(Located<String>, Located<Block>)>, finally: Option<Located<Block>>, scope: usize },
+}
+
+#[derive(Clone)]
 pub enum Value {
     Int(i64), Float(f64),
     Boolean(bool), String(String),
     Vector(Vec<Self>),
+    Map(HashMap<Self, Self>),
+    Set(HashSet<Self>),
     Object(usize),
-    Function(usize)
+    Function(usize, Rc<Meta>),
+    Closure(usize),
+    Iterator(usize),
+    Date(Date),
+    Duration(i64),
+    Regex(usize)
+}
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Int(v1), Value::Int(v2)) => v1 == v2,
+            (Value::Float(v1), Value::Float(v2)) => v1 == v2 || (v1.is_nan() && v2.is_nan()),
+            (Value::Boolean(v1), Value::Boolean(v2)) => v1 == v2,
+            (Value::String(v1), Value::String(v2)) => v1 == v2,
+            (Value::Vector(v1), Value::Vector(v2)) => v1 == v2,
+            (Value::Map(v1), Value::Map(v2)) => v1 == v2,
+            (Value::Set(v1), Value::Set(v2)) => v1 == v2,
+            (Value::Object(v1), Value::Object(v2)) => v1 == v2,
+            (Value::Function(v1, _), Value::Function(v2, _)) => v1 == v2,
+            (Value::Closure(v1), Value::Closure(v2)) => v1 == v2,
+            (Value::Iterator(v1), Value::Iterator(v2)) => v1 == v2,
+            (Value::Date(v1), Value::Date(v2)) => v1 == v2,
+            (Value::Duration(v1), Value::Duration(v2)) => v1 == v2,
+            (Value::Regex(v1), Value::Regex(v2)) => v1 == v2,
+            _ => false
+        }
+    }
+}
+impl Eq for Value {}
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Value::Int(v) => v.hash(state),
+            Value::Float(v) => if v.is_nan() {
+                f64::NAN.to_bits().hash(state)
+            } else if *v == 0.0 {
+                0.0f64.to_bits().hash(state)
+            } else {
+                v.to_bits().hash(state)
+            }
+            Value::Boolean(v) => v.hash(state),
+            Value::String(v) => v.hash(state),
+            Value::Vector(v) => v.hash(state),
+            Value::Map(v) => {
+                let mut hash = 0u64;
+                for entry in v {
+                    let mut hasher = DefaultHasher::new();
+                    entry.hash(&mut hasher);
+                    hash = hash.wrapping_add(hasher.finish());
+                }
+                hash.hash(state)
+            }
+            Value::Set(v) => {
+                let mut hash = 0u64;
+                for value in v {
+                    let mut hasher = DefaultHasher::new();
+                    value.hash(&mut hasher);
+                    hash = hash.wrapping_add(hasher.finish());
+                }
+                hash.hash(state)
+            }
+            Value::Object(v) => v.hash(state),
+            Value::Function(v, _) => v.hash(state),
+            Value::Closure(v) => v.hash(state),
+            Value::Iterator(v) => v.hash(state),
+            Value::Date(v) => v.hash(state),
+            Value::Duration(v) => v.hash(state),
+            Value::Regex(v) => v.hash(state),
+        }
+    }
 }
 impl Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -73,8 +214,15 @@ impl Display for Value {
             Value::Boolean(v) => write!(f, "{v}"),
             Value::String(v) => write!(f, "{v}"),
             Value::Vector(v) => write!(f, "[{}]", v.iter().map(|x| format!("{x:?}")).collect::<Vec<String>>().join(", ")),
-            Value::Function(v) => write!(f, "function:{:8x?}", v as *const usize),
+            Value::Map(v) => write!(f, "{{{}}}", v.iter().map(|(k, v)| format!("{k:?}: {v:?}")).collect::<Vec<String>>().join(", ")),
+            Value::Set(v) => write!(f, "{{{}}}", v.iter().map(|x| format!("{x:?}")).collect::<Vec<String>>().join(", ")),
+            Value::Function(_, meta) => write!(f, "<fn {meta}>"),
+            Value::Closure(v) => write!(f, "function:{:8x?}", v as *const usize),
+            Value::Iterator(v) => write!(f, "iterator:{:8x?}", v as *const usize),
             Value::Object(v) => write!(f, "object:{:8x?}", v as *const usize),
+            Value::Date(v) => write!(f, "{v}"),
+            Value::Duration(v) => write!(f, "{}", format_duration(*v)),
+            Value::Regex(v) => write!(f, "regex:{:8x?}", v as *const usize),
         }
     }
 }
@@ -86,8 +234,15 @@ impl Debug for Value {
             Value::Boolean(v) => write!(f, "{v:?}"),
             Value::String(v) => write!(f, "{v:?}"),
             Value::Vector(v) => write!(f, "[{}]", v.iter().map(|x| format!("{x:?}")).collect::<Vec<String>>().join(", ")),
-            Value::Function(v) => write!(f, "function:{:8x?}", v as *const usize),
+            Value::Map(v) => write!(f, "{{{}}}", v.iter().map(|(k, v)| format!("{k:?}: {v:?}")).collect::<Vec<String>>().join(", ")),
+            Value::Set(v) => write!(f, "{{{}}}", v.iter().map(|x| format!("{x:?}")).collect::<Vec<String>>().join(", ")),
+            Value::Function(_, meta) => write!(f, "<fn {meta}>"),
+            Value::Closure(v) => write!(f, "function:{:8x?}", v as *const usize),
+            Value::Iterator(v) => write!(f, "iterator:{:8x?}", v as *const usize),
             Value::Object(v) => write!(f, "object:{:8x?}", v as *const usize),
+            Value::Date(v) => write!(f, "{v}"),
+            Value::Duration(v) => write!(f, "{}", format_duration(*v)),
+            Value::Regex(v) => write!(f, "regex:{:8x?}", v as *const usize),
         }
     }
 }
\ No newline at end of file
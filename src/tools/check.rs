@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+use crate::structure::position::{Located, Position};
+
+pub trait Check<T> {
+    fn check(&self, checker: &mut Checker<T>) -> T;
+}
+pub struct Checker<T> {
+    scopes: Vec<HashMap<String, (T, bool)>>,
+    pub errors: Vec<Located<String>>
+}
+impl<T> Checker<T> {
+    pub fn new(globals: HashMap<String, (T, bool)>) -> Self {
+        Self { scopes: vec![globals], errors: vec![] }
+    }
+    pub fn get(&self, ident: &str) -> Option<&(T, bool)> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(ident))
+    }
+    pub fn set(&mut self, ident: &str, typ: T, declared: bool) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(ident.to_string(), (typ, declared));
+        }
+    }
+    pub fn assign(&mut self, ident: &str, f: impl FnOnce(&T) -> T) {
+        if let Some(scope) = self.scopes.iter_mut().rev().find(|scope| scope.contains_key(ident)) {
+            if let Some((typ, _)) = scope.get_mut(ident) {
+                *typ = f(typ);
+            }
+        }
+    }
+    pub fn push(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+    pub fn pop(&mut self) {
+        if self.scopes.len() > 1 {
+            self.scopes.pop();
+        }
+    }
+    pub fn error(&mut self, msg: String, pos: Position) {
+        self.errors.push(Located::new(msg, pos));
+    }
+}
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 use crate::structure::position::Located;
 
-pub trait Evaluate<V, P> {
-    fn evaluate(self, program: &mut P) -> Result<Option<V>, Located<String>>;
+pub trait Evaluate<V, P, E> {
+    fn evaluate(self, program: &mut P) -> Result<Option<V>, Located<E>>;
 }
\ No newline at end of file
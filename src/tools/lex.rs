@@ -16,7 +16,7 @@ impl<T: Lexable> Lexer<T> {
         Self { input, idx: 0, ln: 0, col: 0, tokens: vec![] }
     }
     pub fn get(&self) -> Option<char> {
-        self.input.get(self.idx..=self.idx)?.chars().next()
+        self.input.get(self.idx..)?.chars().next()
     }
     pub fn pos(&self) -> Position {
         Position::new(self.ln..self.ln+1, self.col..self.col+1)
@@ -29,13 +29,17 @@ impl<T: Lexable> Lexer<T> {
         }
     }
     pub fn advance(&mut self) {
-        if self.get() == Some('\n') {
+        let Some(c) = self.get() else {
+            self.idx += 1;
+            return
+        };
+        if c == '\n' {
             self.ln += 1;
             self.col = 0;
         } else {
             self.col += 1;
         }
-        self.idx += 1;
+        self.idx += c.len_utf8();
     }
     pub fn advance_while<F: Fn(char) -> bool>(&mut self, f: &F) {
         while self.check(f) {
@@ -1,3 +1,4 @@
 pub mod lex;
 pub mod parse;
-pub mod evaluate;
\ No newline at end of file
+pub mod evaluate;
+pub mod check;
\ No newline at end of file
@@ -0,0 +1,90 @@
+use std::process::Command;
+
+#[test]
+fn scripts() {
+    let output = Command::new(env!("CARGO_BIN_EXE_evalio"))
+        .arg("test")
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/tests"))
+        .output()
+        .expect("cannot run evalio");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{stdout}");
+}
+
+fn eval(input: &str) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_evalio"))
+        .arg(input)
+        .output()
+        .expect("cannot run evalio");
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+#[test]
+fn statements() {
+    assert_eq!(eval("let a = 1; a = 2; a"), "2");
+    assert_eq!(eval("const a = 1; a = 2"), "ERROR: cannot assign to constant \"a\"");
+}
+
+#[test]
+fn annotations() {
+    assert_eq!(eval("let v: [int] = [1]; v[0] = \"a\""), "ERROR: 1:28: expected int, got string");
+    assert_eq!(eval("let v = [1]; v[0] = \"a\"; v"), "[\"a\"]");
+    assert_eq!(eval("let x: int | none = none"), "ERROR: 1:8: variables cannot hold none, remove it from int | none");
+    assert_eq!(eval("let x: int | none = 1"), "ERROR: 1:8: variables cannot hold none, remove it from int | none");
+}
+
+#[test]
+fn scoping() {
+    assert_eq!(eval("for i in [1] { let n = 1 }; n"), "ERROR: no variable with the name \"n\" found");
+    assert_eq!(eval("let f = fn() { let m = 1 }; f(); m"), "ERROR: no variable with the name \"m\" found");
+}
+
+fn status(input: &str) -> Option<i32> {
+    let output = Command::new(env!("CARGO_BIN_EXE_evalio"))
+        .arg(input)
+        .output()
+        .expect("cannot run evalio");
+    output.status.code()
+}
+
+#[test]
+fn exit_status() {
+    assert_eq!(status("exit(3)"), Some(3));
+    assert_eq!(status("exit()"), Some(0));
+    assert_eq!(status("try exit(4) catch e { 1 }"), Some(4));
+    assert_eq!(status("vector.map([1], fn(x: int) { exit(6) })"), Some(6));
+    assert_eq!(status("assert_error(fn() { exit(7) })"), Some(7));
+}
+
+fn repl(input: &str) -> (Option<i32>, String) {
+    use std::{io::Write, process::Stdio};
+    let mut child = Command::new(env!("CARGO_BIN_EXE_evalio"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("cannot run evalio");
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().expect("cannot run evalio");
+    (output.status.code(), String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[test]
+fn repl_exits_on_eof() {
+    let (code, stdout) = repl("1 + 1\n");
+    assert_eq!(code, Some(0));
+    assert!(stdout.contains("> 2"), "{stdout}");
+    assert_eq!(repl("").0, Some(0));
+    assert_eq!(repl("exit(5)\n1\n").0, Some(5));
+}
+
+#[test]
+fn introspection() {
+    let abs = "abs(x)\n    returns the absolute value of a number";
+    assert_eq!(eval("abs"), "<fn abs(x)>");
+    assert_eq!(eval("str(math.log)"), "<fn math.log(x, base)>");
+    assert_eq!(eval("help(abs)"), abs);
+    assert!(eval("help(math)").contains("math.log(x, base)\n    returns the logarithm in the given base"));
+    assert!(eval("help()").contains(abs));
+    assert_eq!(eval("help(1)"), "ERROR: expected function or object for argument #1, got int");
+    assert!(repl(":help abs\n").1.contains(abs));
+}